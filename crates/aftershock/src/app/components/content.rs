@@ -1,5 +1,7 @@
 use leptos::prelude::*;
 
+use crate::config::use_site_config;
+
 #[component]
 pub fn ContentSerif(children: Children) -> impl IntoView {
     view! { <div class="font-af-serif font-medium text-xl">{children()}</div> }
@@ -10,12 +12,84 @@ pub fn ContentSans(children: Children) -> impl IntoView {
     view! { <div class="font-af-sans font-medium text-xl">{children()}</div> }
 }
 
+/// A rendered body. Once hydrated, its code blocks get a copy button.
 #[component]
 pub fn ProseContent(body: String) -> impl IntoView {
+    let messages = use_site_config().messages;
+    let content = NodeRef::<leptos::html::Div>::new();
+    add_copy_buttons(content, messages.copy_code);
+    let code_copied = messages.code_copied;
+
     view! {
         <div
+            node_ref=content
             class="max-w-none prose md:prose-lg prose-stone dark:prose-invert prose-table:mx-2 prose-pre:font-af-mono prose-a:no-underline prose-a:text-blue-500 prose-a:hover:underline prose-table:overflow-x-auto prose-table:block prose-table:md:table"
+            on:click=move |ev| copy_code(ev, &code_copied)
             inner_html=body
         />
     }
 }
+
+/// Put a `hl-copy` button in every code block of `content`.
+#[cfg(feature = "hydrate")]
+fn add_copy_buttons(content: NodeRef<leptos::html::Div>, label: String) {
+    use wasm_bindgen::JsCast;
+
+    Effect::new(move |_| {
+        let Some(content) = content.get() else {
+            return;
+        };
+        let Ok(blocks) = content.query_selector_all(".hl-block") else {
+            return;
+        };
+        for block in (0..blocks.length()).filter_map(|n| blocks.item(n)) {
+            let Ok(block) = block.dyn_into::<web_sys::Element>() else {
+                continue;
+            };
+            if block.query_selector(".hl-copy").ok().flatten().is_some() {
+                continue;
+            }
+            if let Ok(button) = document().create_element("button") {
+                let _ = button.set_attribute("type", "button");
+                let _ = button.set_attribute("class", "hl-copy");
+                button.set_text_content(Some(&label));
+                let _ = block.append_child(&button);
+            }
+        }
+    });
+}
+
+#[cfg(not(feature = "hydrate"))]
+fn add_copy_buttons(_content: NodeRef<leptos::html::Div>, _label: String) {}
+
+/// Copy the code of the block whose `hl-copy` button was clicked.
+#[cfg(feature = "hydrate")]
+fn copy_code(ev: leptos::ev::MouseEvent, copied: &str) {
+    use wasm_bindgen::JsCast;
+
+    let Some(button) = ev
+        .target()
+        .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+        .and_then(|element| element.closest(".hl-copy").ok().flatten())
+    else {
+        return;
+    };
+    let code = button
+        .closest(".hl-block")
+        .ok()
+        .flatten()
+        .and_then(|block| block.query_selector("code").ok().flatten())
+        .and_then(|code| code.text_content());
+    if let Some(code) = code {
+        let _ = window().navigator().clipboard().write_text(&code);
+        let label = button.text_content();
+        button.set_text_content(Some(copied));
+        set_timeout(
+            move || button.set_text_content(label.as_deref()),
+            std::time::Duration::from_secs(2),
+        );
+    }
+}
+
+#[cfg(not(feature = "hydrate"))]
+fn copy_code(_ev: leptos::ev::MouseEvent, _copied: &str) {}
@@ -0,0 +1,121 @@
+use chrono::{TimeZone, Utc};
+use leptos::prelude::*;
+use leptos_meta::{Link, Meta, Script};
+use serde_json::json;
+
+use crate::config::use_site_config;
+
+/// Serialize `value` for a `<script>` block. `<`, `>` and `&` only ever appear inside
+/// JSON strings, where their `\u` escapes mean the same, so the text stays intact whatever
+/// the renderer escapes.
+fn json_ld(value: serde_json::Value) -> String {
+    value
+        .to_string()
+        .replace('<', "\\u003c")
+        .replace('>', "\\u003e")
+        .replace('&', "\\u0026")
+}
+
+fn rfc3339(timestamp: i64) -> String {
+    Utc.timestamp_opt(timestamp, 0)
+        .single()
+        .unwrap_or_default()
+        .to_rfc3339()
+}
+
+/// Description, Open Graph and Twitter card of the site itself, with its `WebSite` data.
+/// `path` is the canonical path of the listing showing it.
+#[component]
+pub fn SiteHead(#[prop(into, default = "/".into())] path: String) -> impl IntoView {
+    let config = use_site_config();
+    let url = format!("{}{path}", config.base_url);
+    let data = json_ld(json!({
+        "@context": "https://schema.org",
+        "@type": "WebSite",
+        "name": config.title,
+        "description": config.description,
+        "url": url,
+        "inLanguage": config.language,
+        "author": {"@type": "Person", "name": config.author},
+    }));
+
+    view! {
+        <Meta name="description" content=config.description.clone() />
+        <Link rel="canonical" href=url.clone() />
+        <Meta property="og:type" content="website" />
+        <Meta property="og:site_name" content=config.title.clone() />
+        <Meta property="og:title" content=config.title.clone() />
+        <Meta property="og:description" content=config.description.clone() />
+        <Meta property="og:url" content=url />
+        <Meta name="twitter:card" content="summary" />
+        <Meta name="twitter:title" content=config.title />
+        <Meta name="twitter:description" content=config.description />
+        <Script type_="application/ld+json">{data}</Script>
+    }
+}
+
+/// Everything a shared link to a post or page needs: description, canonical URL, Open
+/// Graph and Twitter cards, and `BlogPosting` data for posts.
+#[component]
+pub fn PostHead(post: aftershock_bridge::Post) -> impl IntoView {
+    let config = use_site_config();
+    let is_post = post.kind == "post";
+    let url = match is_post {
+        true => format!("{}/posts/{}", config.base_url, post.uid),
+        false => format!("{}/{}", config.base_url, post.uid),
+    };
+    let description = post.summary.clone().unwrap_or(config.description.clone());
+    let image = format!("{}/og/{}.png", config.base_url, post.uid);
+    let published = rfc3339(post.created_at);
+    let modified = rfc3339(post.updated_at);
+
+    let article = is_post.then(|| {
+        let data = json_ld(json!({
+            "@context": "https://schema.org",
+            "@type": "BlogPosting",
+            "headline": post.title,
+            "description": description,
+            "url": url,
+            "mainEntityOfPage": url,
+            "datePublished": published,
+            "dateModified": modified,
+            "keywords": post.tags,
+            "inLanguage": config.language,
+            "author": {"@type": "Person", "name": config.author},
+            "publisher": {"@type": "Organization", "name": config.title},
+            "image": image,
+        }));
+
+        view! {
+            <Meta property="og:image" content=image.clone() />
+            <Meta property="og:image:width" content="1200" />
+            <Meta property="og:image:height" content="630" />
+            <Meta name="twitter:card" content="summary_large_image" />
+            <Meta name="twitter:image" content=image />
+            <Meta property="article:published_time" content=published />
+            <Meta property="article:modified_time" content=modified />
+            <Meta property="article:author" content=config.author.clone() />
+            {post
+                .tags
+                .iter()
+                .map(|tag| view! { <Meta property="article:tag" content=tag.clone() /> })
+                .collect_view()}
+            <Script type_="application/ld+json">{data}</Script>
+        }
+    });
+
+    view! {
+        <Meta name="description" content=description.clone() />
+        <Link rel="canonical" href=url.clone() />
+        <Meta property="og:type" content=if is_post { "article" } else { "website" } />
+        <Meta property="og:site_name" content=config.title.clone() />
+        <Meta property="og:title" content=post.title.clone() />
+        <Meta property="og:description" content=description.clone() />
+        <Meta property="og:url" content=url />
+        <Meta property="og:locale" content=config.language.replace('-', "_") />
+        {(!is_post).then(|| view! { <Meta name="twitter:card" content="summary" /> })}
+        <Meta name="twitter:title" content=post.title />
+        <Meta name="twitter:description" content=description />
+        {article}
+    }
+}
@@ -1,36 +1,33 @@
 use leptos::prelude::*;
 
+use crate::config::use_site_config;
+
 #[component]
 pub fn License() -> impl IntoView {
+    let license = use_site_config().license;
+
     view! {
         <div class="flex flex-row gap-1 max-w-none my-4">
             <a
-                href="https://creativecommons.org/licenses/by-nc-sa/4.0/"
+                href=license.url
                 class="flex flex-row"
                 target="_blank"
                 rel="noopener noreferrer"
-                title="CC BY-NC-SA"
+                title=license.name
             >
-                <img
-                    style="height:22px!important;margin-left:3px;vertical-align:text-bottom;"
-                    src="https://mirrors.creativecommons.org/presskit/icons/cc.svg?ref=chooser-v1"
-                    alt=""
-                />
-                <img
-                    style="height:22px!important;margin-left:3px;vertical-align:text-bottom;"
-                    src="https://mirrors.creativecommons.org/presskit/icons/by.svg?ref=chooser-v1"
-                    alt=""
-                />
-                <img
-                    style="height:22px!important;margin-left:3px;vertical-align:text-bottom;"
-                    src="https://mirrors.creativecommons.org/presskit/icons/nc.svg?ref=chooser-v1"
-                    alt=""
-                />
-                <img
-                    style="height:22px!important;margin-left:3px;vertical-align:text-bottom;"
-                    src="https://mirrors.creativecommons.org/presskit/icons/sa.svg?ref=chooser-v1"
-                    alt=""
-                />
+                {license
+                    .icons
+                    .into_iter()
+                    .map(|icon| {
+                        view! {
+                            <img
+                                style="height:22px!important;margin-left:3px;vertical-align:text-bottom;"
+                                src=icon
+                                alt=""
+                            />
+                        }
+                    })
+                    .collect_view()}
             </a>
         </div>
     }
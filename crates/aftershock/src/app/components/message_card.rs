@@ -1,6 +1,9 @@
 use leptos::prelude::*;
 
-use crate::app::components::ContentSans;
+use crate::{
+    app::{components::ContentSans, server::AppError},
+    config::use_site_config,
+};
 
 #[component]
 pub fn MessageBox(msg: ReadSignal<String>) -> impl IntoView {
@@ -12,3 +15,24 @@ pub fn MessageBox(msg: ReadSignal<String>) -> impl IntoView {
         </div>
     }
 }
+
+/// The message for `error`. Rendered on the server, it also sets the status of the
+/// response, so the data it stands for has to come from a blocking resource.
+#[component]
+pub fn ErrorMessage(error: AppError) -> impl IntoView {
+    #[cfg(feature = "ssr")]
+    if let Some(response) = use_context::<leptos_axum::ResponseOptions>() {
+        response.set_status(error.status());
+    }
+
+    let messages = use_site_config().messages;
+    let (msg, _) = signal(match error {
+        // Nothing tells a forbidden preview from a missing one
+        AppError::NotFound | AppError::Forbidden => messages.data_not_found,
+        AppError::BadRequest(_) => messages.bad_request,
+        AppError::Unavailable(_) => messages.storage_unavailable,
+        AppError::InvalidResponse(_) | AppError::ServerFn(_) => messages.load_data_failure,
+    });
+
+    view! { <MessageBox msg=msg /> }
+}
@@ -1,17 +1,31 @@
 mod content;
+mod head;
 mod license;
 mod message_card;
+mod pagination;
 mod post;
 mod post_meta;
+mod post_neighbors;
+mod protected_post;
+mod search;
 mod sidebar;
 mod tag;
+mod theme_toggle;
 mod time;
+mod toc;
 
 pub use content::*;
+pub use head::*;
 pub use license::*;
 pub use message_card::*;
+pub use pagination::*;
 pub use post::*;
 pub use post_meta::*;
+pub use post_neighbors::*;
+pub use protected_post::*;
+pub use search::*;
 pub use sidebar::*;
 pub use tag::*;
+pub use theme_toggle::*;
 pub use time::*;
+pub use toc::*;
@@ -0,0 +1,52 @@
+use leptos::prelude::*;
+use leptos_router::components::A;
+
+/// Link to the `page`th page of a listing living at `base`, the first page is `base` itself.
+pub fn page_url(base: &str, page: i64) -> String {
+    match page {
+        1 => base.to_owned(),
+        page => format!("{}/page/{page}", base.trim_end_matches('/')),
+    }
+}
+
+/// Pages worth a link around `current`: the ends, the neighbours and gaps as `None`.
+fn visible_pages(current: i64, total_pages: i64) -> Vec<Option<i64>> {
+    let mut ret = vec![];
+    for page in 1..=total_pages {
+        if page == 1 || page == total_pages || (page - current).abs() <= 2 {
+            ret.push(Some(page));
+        } else if ret.last() != Some(&None) {
+            ret.push(None);
+        }
+    }
+    ret
+}
+
+#[component]
+pub fn Pagination(current: i64, total_pages: i64, base: String) -> impl IntoView {
+    if total_pages <= 1 {
+        return ().into_any();
+    }
+
+    let prev = (current > 1).then(|| page_url(&base, current - 1));
+    let next = (current < total_pages).then(|| page_url(&base, current + 1));
+
+    view! {
+        <nav class="flex flex-row flex-wrap justify-center gap-3 my-6 font-af-serif font-semibold">
+            {prev.map(|url| view! { <A href=url attr:rel="prev">"←"</A> })}
+            {visible_pages(current, total_pages)
+                .into_iter()
+                .map(|page| match page {
+                    Some(page) if page == current => {
+                        view! { <span aria-current="page" class="underline">{page}</span> }
+                            .into_any()
+                    }
+                    Some(page) => view! { <A href=page_url(&base, page)>{page}</A> }.into_any(),
+                    None => view! { <span>"…"</span> }.into_any(),
+                })
+                .collect_view()}
+            {next.map(|url| view! { <A href=url attr:rel="next">"→"</A> })}
+        </nav>
+    }
+    .into_any()
+}
@@ -1,21 +1,27 @@
 use leptos::prelude::*;
 use leptos_meta::Title;
 
-use crate::app::components::{
-    content::ContentSerif, AfTime, License, ProseContent, TagListWithoutUl,
+use crate::{
+    app::components::{
+        content::ContentSerif, AfTime, License, ProseContent, TableOfContents, TagListWithoutUl,
+    },
+    config::use_site_config,
 };
 
 #[component]
 pub fn Post(post: aftershock_bridge::Post) -> impl IntoView {
+    let site_title = use_site_config().title;
+
     view! {
-        <Title text=format!("{} - {}", post.title, crate::consts::TITLE) />
-        <article class="flex flex-col gap-0">
+        <Title text=format!("{} - {}", post.title, site_title) />
+        <article class="relative flex flex-col gap-0">
             <h1 class="font-af-serif text-3xl font-bold">{post.title}</h1>
             <div class="grid grid-flow-col gap-2 justify-start font-af-serif font-medium">
                 <AfTime timestamp=post.created_at />
                 <TagList tags=post.tags />
             </div>
             <div class="my-5"></div>
+            {(!post.toc.is_empty()).then(|| view! { <TableOfContents toc=post.toc /> })}
             <ContentSerif>
                 <ProseContent body=post.body />
             </ContentSerif>
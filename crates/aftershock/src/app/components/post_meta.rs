@@ -4,7 +4,7 @@ use leptos_router::components::A;
 use crate::{
     app::components::TagListWithoutUl,
     utils::{
-        datetime::{AppDateTime, DateTime},
+        datetime::{use_display_timezone, use_time_settings, AppDateTime},
         group_by,
     },
 };
@@ -14,13 +14,19 @@ pub fn PostMetaListGroupByTime(
     post_meta_list: Vec<aftershock_bridge::PostMeta>,
     with_summary: bool,
 ) -> impl IntoView {
+    // Sections always follow the site timezone so they never move after hydration
+    let settings = use_time_settings();
     let posts = post_meta_list
         .into_iter()
-        .map(|post| (AppDateTime::from_timestamp(post.created_at), post))
+        .map(|post| {
+            let time =
+                AppDateTime::from_timestamp(post.created_at, settings.timezone, settings.locale);
+            (time, post)
+        })
         .collect::<Vec<_>>();
     let posts = group_by(posts, |post| post.0.year(), |post| post.clone());
     let mut posts = posts.into_iter().collect::<Vec<_>>();
-    posts.sort_unstable_by(|lhs, rhs| rhs.0.cmp(&lhs.0));
+    posts.sort_unstable_by_key(|x| std::cmp::Reverse(x.0));
 
     view! {
         <div class="flex flex-col gap-4 font-af-serif">
@@ -46,13 +52,18 @@ pub fn PostMetaListGroupByTag(
     post_meta_list: Vec<aftershock_bridge::PostMeta>,
     primary_tag: String,
 ) -> impl IntoView {
+    let settings = use_time_settings();
     let posts = post_meta_list
         .into_iter()
-        .map(|post| (AppDateTime::from_timestamp(post.created_at), post))
+        .map(|post| {
+            let time =
+                AppDateTime::from_timestamp(post.created_at, settings.timezone, settings.locale);
+            (time, post)
+        })
         .collect::<Vec<_>>();
     let posts = group_by(posts, |post| post.0.year(), |post| post.clone());
     let mut posts = posts.into_iter().collect::<Vec<_>>();
-    posts.sort_unstable_by(|lhs, rhs| rhs.0.cmp(&lhs.0));
+    posts.sort_unstable_by_key(|x| std::cmp::Reverse(x.0));
     let posts = posts.into_iter().flat_map(|(_, x)| x).collect();
 
     view! {
@@ -89,8 +100,12 @@ pub fn PostMeta(
     with_summary: bool,
 ) -> impl IntoView {
     let url = format!("/posts/{}", post_meta.uid);
-    let human_time = format!("{} {}", time.month_to_abbr(), time.day());
-    let machine_time = time.machine_friendly().to_owned();
+    let locale = use_time_settings().locale;
+    let timezone = use_display_timezone();
+    let timestamp = time.orig();
+    let time = move || AppDateTime::from_timestamp(timestamp, timezone.get(), locale);
+    let human_time = move || time().month_day();
+    let machine_time = move || time().machine_friendly().to_owned();
 
     view! {
         <div class="flex flex-col gap-1 sm:gap-2 md:gap-4">
@@ -0,0 +1,54 @@
+use leptos::prelude::*;
+use leptos_router::components::A;
+
+use crate::config::use_site_config;
+
+#[component]
+pub fn PostNeighbors(neighbors: aftershock_bridge::PostNeighbors) -> impl IntoView {
+    let messages = use_site_config().messages;
+    let link = |post: aftershock_bridge::PostMeta| {
+        view! { <A href=format!("/posts/{}", post.uid)>{post.title}</A> }
+    };
+
+    view! {
+        <nav class="flex flex-col gap-4 mt-8 font-af-serif">
+            <div class="grid grid-cols-2 gap-4 font-semibold">
+                <div class="flex flex-col items-start">
+                    {neighbors
+                        .prev
+                        .map(|post| {
+                            view! {
+                                <span class="text-sm font-medium">{messages.prev_post.clone()}</span>
+                                {link(post)}
+                            }
+                        })}
+                </div>
+                <div class="flex flex-col items-end text-right">
+                    {neighbors
+                        .next
+                        .map(|post| {
+                            view! {
+                                <span class="text-sm font-medium">{messages.next_post.clone()}</span>
+                                {link(post)}
+                            }
+                        })}
+                </div>
+            </div>
+            {(!neighbors.related.is_empty())
+                .then(|| {
+                    view! {
+                        <section class="flex flex-col gap-2">
+                            <h2 class="font-bold text-xl">{messages.related_posts.clone()}</h2>
+                            <ul class="flex flex-col gap-1 font-semibold">
+                                {neighbors
+                                    .related
+                                    .into_iter()
+                                    .map(|post| view! { <li>{link(post)}</li> })
+                                    .collect_view()}
+                            </ul>
+                        </section>
+                    }
+                })}
+        </nav>
+    }
+}
@@ -0,0 +1,68 @@
+use leptos::prelude::*;
+use leptos_meta::Title;
+
+use crate::{
+    app::{
+        components::{ContentSans, Post},
+        server::{AppError, UnlockPost},
+    },
+    config::use_site_config,
+};
+
+/// A password protected post whose body has been withheld, asks for the password and
+/// swaps in the full post once the storage accepts it.
+#[component]
+pub fn ProtectedPost(post: aftershock_bridge::Post) -> impl IntoView {
+    let config = use_site_config();
+    let unlock = ServerAction::<UnlockPost>::new();
+    let title = post.title.clone();
+    let uid = post.uid.clone();
+
+    view! {
+        {move || match unlock.value().get() {
+            Some(Ok(post)) => view! { <Post post=post /> }.into_any(),
+            result => {
+                let (title, uid) = (title.clone(), uid.clone());
+                let messages = config.messages.clone();
+                view! {
+                    <Title text=format!("{} - {}", title, config.title) />
+                    <article class="flex flex-col gap-0">
+                        <h1 class="font-af-serif text-3xl font-bold">{title}</h1>
+                        <div class="my-5"></div>
+                        <ContentSans>
+                            <p>{messages.protected}</p>
+                            <ActionForm action=unlock>
+                                <input type="hidden" name="uid" value=uid />
+                                <div class="flex gap-2">
+                                    <input
+                                        type="password"
+                                        name="password"
+                                        required
+                                        class="border-2 border-site-dark rounded-lg px-2 py-1"
+                                    />
+                                    <button
+                                        type="submit"
+                                        class="border-2 border-site-dark rounded-lg px-3 py-1"
+                                    >
+                                        "→"
+                                    </button>
+                                </div>
+                            </ActionForm>
+                            {result
+                                .and_then(Result::err)
+                                .map(|error| {
+                                    let msg = match error {
+                                        AppError::Forbidden => messages.wrong_password,
+                                        AppError::Unavailable(_) => messages.storage_unavailable,
+                                        _ => messages.load_data_failure,
+                                    };
+                                    view! { <p>{msg}</p> }
+                                })}
+                        </ContentSans>
+                    </article>
+                }
+                    .into_any()
+            }
+        }}
+    }
+}
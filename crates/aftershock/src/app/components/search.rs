@@ -0,0 +1,61 @@
+use leptos::prelude::*;
+use leptos_router::components::Form;
+
+use crate::{
+    app::components::PostMeta,
+    config::use_site_config,
+    utils::datetime::{use_time_settings, AppDateTime},
+};
+
+/// A plain GET form, so searching works before or without hydration.
+#[component]
+pub fn SearchBox() -> impl IntoView {
+    let placeholder = use_site_config().messages.search_placeholder;
+
+    view! {
+        <Form action="/search" method="get" attr:role="search" attr:class="flex justify-end">
+            <input
+                type="search"
+                name="q"
+                placeholder=placeholder.clone()
+                aria-label=placeholder
+                class="w-40 border-b border-site-dark bg-transparent px-1 focus:outline-none"
+            />
+        </Form>
+    }
+}
+
+#[component]
+pub fn SearchHitList(hits: Vec<aftershock_bridge::SearchHit>) -> impl IntoView {
+    let settings = use_time_settings();
+
+    view! {
+        <ol class="flex flex-col gap-6 font-af-serif">
+            {hits
+                .into_iter()
+                .map(|hit| {
+                    let time = AppDateTime::from_timestamp(
+                        hit.post.created_at,
+                        settings.timezone,
+                        settings.locale,
+                    );
+                    view! {
+                        <li class="flex flex-col gap-1">
+                            <PostMeta time=time post_meta=hit.post with_summary=false />
+                            <p class="font-medium mx-1">
+                                {hit
+                                    .snippet
+                                    .into_iter()
+                                    .map(|part| match part.highlight {
+                                        true => view! { <mark>{part.text}</mark> }.into_any(),
+                                        false => part.text.into_any(),
+                                    })
+                                    .collect_view()}
+                            </p>
+                        </li>
+                    }
+                })
+                .collect_view()}
+        </ol>
+    }
+}
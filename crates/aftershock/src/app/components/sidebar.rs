@@ -1,36 +1,79 @@
 use leptos::prelude::*;
 use leptos_router::components::A;
 
-use crate::TITLE;
+use crate::{
+    app::{
+        components::{SearchBox, ThemeToggle},
+        server::get_nav_pages,
+    },
+    config::use_site_config,
+};
 
 #[component]
 pub fn Header() -> impl IntoView {
+    let config = use_site_config();
+    let title = config.title.clone();
+    let pages = Resource::new(
+        || (),
+        |_| async move { get_nav_pages().await.unwrap_or_default() },
+    );
+
     view! {
         <header class="grid grid-flow-row gap-2 font-af-serif pt-4">
-            <A href="/" attr:title=TITLE attr:class="text-2xl font-bold">
-                {TITLE}
+            <A href="/" attr:title=title attr:class="text-2xl font-bold">
+                {config.title}
             </A>
             <nav>
                 <ul class="grid grid-flow-col gap-4 justify-end font-semibold">
-                    <li class="max-w-fit">
-                        <A href="/">"主页"</A>
-                    </li>
-                    <li class="max-w-fit">
-                        <A href="/about">"关于"</A>
-                    </li>
+                    {config
+                        .nav
+                        .into_iter()
+                        .map(|link| {
+                            view! {
+                                <li class="max-w-fit">
+                                    <A href=link.href>{link.title}</A>
+                                </li>
+                            }
+                        })
+                        .collect_view()}
+                    <Transition>
+                        {move || {
+                            pages
+                                .get()
+                                .map(|pages| {
+                                    pages
+                                        .into_iter()
+                                        .map(|page| {
+                                            let href = format!("/{}", page.uid);
+                                            let title = page
+                                                .nav
+                                                .and_then(|nav| nav.title)
+                                                .unwrap_or(page.title);
+                                            view! {
+                                                <li class="max-w-fit">
+                                                    <A href=href>{title}</A>
+                                                </li>
+                                            }
+                                        })
+                                        .collect_view()
+                                })
+                        }}
+                    </Transition>
                 </ul>
             </nav>
+            <SearchBox />
+            <ThemeToggle />
             <div class="header-line w-full border border-site-dark"></div>
         </header>
     }
 }
 
 #[component]
-pub fn Footer() -> impl IntoView {
+pub fn Footer(text: String) -> impl IntoView {
     view! {
-        <footer>
+        <footer class="font-af-serif text-sm pb-4">
             <div>"Powered by Aftershock"</div>
-            <div>"(c) 2025 Aspirin"</div>
+            <div>{text}</div>
         </footer>
     }
 }
@@ -0,0 +1,135 @@
+use leptos::prelude::*;
+
+use crate::config::use_site_config;
+
+#[cfg(any(feature = "ssr", feature = "hydrate"))]
+const THEME_COOKIE: &str = "theme";
+
+/// The color scheme the reader picked, kept in the `theme` cookie. `System` leaves it to
+/// `prefers-color-scheme`, the others put their class on `<html>`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Theme {
+    Light,
+    Dark,
+    #[default]
+    System,
+}
+
+impl Theme {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Theme::Light => "light",
+            Theme::Dark => "dark",
+            Theme::System => "system",
+        }
+    }
+
+    /// The class of `<html>`, none to follow the system.
+    pub fn class(&self) -> Option<&'static str> {
+        match self {
+            Theme::System => None,
+            theme => Some(theme.as_str()),
+        }
+    }
+
+    #[cfg(any(feature = "ssr", feature = "hydrate"))]
+    fn from_cookies(cookies: &str) -> Self {
+        cookies
+            .split(';')
+            .filter_map(|x| x.trim().split_once('='))
+            .find(|(name, _)| *name == THEME_COOKIE)
+            .map(|(_, value)| match value {
+                "light" => Theme::Light,
+                "dark" => Theme::Dark,
+                _ => Theme::System,
+            })
+            .unwrap_or_default()
+    }
+
+    /// The theme of the request being rendered.
+    #[cfg(feature = "ssr")]
+    pub fn current() -> Self {
+        use_context::<axum::http::request::Parts>()
+            .and_then(|parts| {
+                let cookies = parts.headers.get_all(axum::http::header::COOKIE);
+                cookies
+                    .iter()
+                    .filter_map(|x| x.to_str().ok())
+                    .map(Theme::from_cookies)
+                    .find(|x| *x != Theme::System)
+            })
+            .unwrap_or_default()
+    }
+
+    /// The theme the page was rendered with.
+    #[cfg(not(feature = "ssr"))]
+    pub fn current() -> Self {
+        #[cfg(feature = "hydrate")]
+        {
+            use wasm_bindgen::JsCast;
+
+            document()
+                .dyn_into::<web_sys::HtmlDocument>()
+                .ok()
+                .and_then(|document| document.cookie().ok())
+                .map(|cookies| Theme::from_cookies(&cookies))
+                .unwrap_or_default()
+        }
+        #[cfg(not(feature = "hydrate"))]
+        Theme::default()
+    }
+
+    /// Remember the theme and switch the page to it.
+    #[cfg(feature = "hydrate")]
+    fn apply(&self) {
+        use wasm_bindgen::JsCast;
+
+        if let Ok(document) = document().dyn_into::<web_sys::HtmlDocument>() {
+            let _ = document.set_cookie(&format!(
+                "{THEME_COOKIE}={}; path=/; max-age=31536000; samesite=lax",
+                self.as_str()
+            ));
+        }
+        if let Some(html) = document().document_element() {
+            let classes = html.class_list();
+            let _ = classes.remove_2("light", "dark");
+            if let Some(class) = self.class() {
+                let _ = classes.add_1(class);
+            }
+        }
+    }
+
+    #[cfg(not(feature = "hydrate"))]
+    fn apply(&self) {}
+}
+
+/// Light, dark or system color scheme, in the header.
+#[component]
+pub fn ThemeToggle() -> impl IntoView {
+    let messages = use_site_config().messages;
+    let (theme, set_theme) = signal(Theme::current());
+
+    let option = move |value: Theme, label: String| {
+        view! {
+            <button
+                type="button"
+                aria-pressed=move || (theme.get() == value).to_string()
+                class="px-1 aria-pressed:font-bold aria-pressed:underline"
+                on:click=move |_| {
+                    value.apply();
+                    set_theme.set(value);
+                }
+            >
+                {label}
+            </button>
+        }
+    };
+
+    view! {
+        <div role="group" aria-label=messages.theme class="flex gap-1 justify-end text-sm">
+            {option(Theme::Light, messages.theme_light)}
+            {option(Theme::Dark, messages.theme_dark)}
+            {option(Theme::System, messages.theme_system)}
+        </div>
+    }
+}
@@ -1,13 +1,16 @@
 use leptos::prelude::*;
 
-use crate::utils::datetime::{AppDateTime, DateTime};
+use crate::utils::datetime::{use_display_timezone, use_time_settings, AppDateTime};
 
 #[component]
 pub fn AfTime(timestamp: i64) -> impl IntoView {
-    let time = AppDateTime::from_timestamp(timestamp);
+    let locale = use_time_settings().locale;
+    let timezone = use_display_timezone();
+    let time = move || AppDateTime::from_timestamp(timestamp, timezone.get(), locale);
+
     view! {
-        <time datetime=time.machine_friendly().to_owned() class="max-w-fit">
-            {time.human_readable().to_owned()}
+        <time datetime=move || time().machine_friendly().to_owned() class="max-w-fit">
+            {move || time().human_readable()}
         </time>
     }
 }
@@ -0,0 +1,91 @@
+use leptos::prelude::*;
+
+use crate::config::use_site_config;
+
+/// Where the active heading is looked for, in pixels from the top of the viewport
+#[cfg(feature = "hydrate")]
+const ACTIVE_OFFSET: f64 = 96.0;
+
+/// The outline of a post: a sticky column beside it on wide screens, a collapsed block
+/// above it otherwise. Once hydrated, the section being read is highlighted.
+#[component]
+pub fn TableOfContents(toc: Vec<aftershock_bridge::TocEntry>) -> impl IntoView {
+    let title = use_site_config().messages.toc;
+    let (active, set_active) = signal(None::<String>);
+    track_active_heading(toc.iter().map(|x| x.id.clone()).collect(), set_active);
+
+    view! {
+        <aside class="hidden xl:block absolute left-full top-0 h-full ml-8 w-56">
+            <nav aria-label=title.clone() class="sticky top-8 font-af-serif text-sm">
+                <TocList toc=toc.clone() active=active />
+            </nav>
+        </aside>
+        <details class="xl:hidden mb-4 font-af-serif">
+            <summary class="cursor-pointer font-semibold">{title}</summary>
+            <TocList toc=toc active=active />
+        </details>
+    }
+}
+
+#[component]
+fn TocList(
+    toc: Vec<aftershock_bridge::TocEntry>,
+    active: ReadSignal<Option<String>>,
+) -> impl IntoView {
+    let top = toc.iter().map(|x| x.level).min().unwrap_or(1);
+
+    view! {
+        <ol class="flex flex-col gap-1 mt-2">
+            {toc
+                .into_iter()
+                .map(|entry| {
+                    let indent = format!("padding-left: {}rem", (entry.level - top) as f32 * 0.75);
+                    let id = entry.id.clone();
+                    let class = move || match active.read().as_deref() == Some(id.as_str()) {
+                        true => "font-bold underline",
+                        false => "font-medium",
+                    };
+                    view! {
+                        <li style=indent>
+                            <a href=format!("#{}", entry.id) class=class>
+                                {entry.title}
+                            </a>
+                        </li>
+                    }
+                })
+                .collect_view()}
+        </ol>
+    }
+}
+
+/// Keep `set_active` on the last heading scrolled past, or the first one.
+#[cfg(feature = "hydrate")]
+fn track_active_heading(ids: Vec<String>, set_active: WriteSignal<Option<String>>) {
+    let update = move || {
+        let document = document();
+        let current = ids
+            .iter()
+            .filter_map(|id| {
+                let top = document
+                    .get_element_by_id(id)?
+                    .get_bounding_client_rect()
+                    .top();
+                Some((id, top))
+            })
+            .take_while(|(_, top)| *top <= ACTIVE_OFFSET)
+            .last()
+            .map(|(id, _)| id.clone())
+            .or_else(|| ids.first().cloned());
+        set_active.set(current);
+    };
+
+    Effect::new({
+        let update = update.clone();
+        move |_| update()
+    });
+    let handle = window_event_listener(leptos::ev::scroll, move |_| update());
+    on_cleanup(move || handle.remove());
+}
+
+#[cfg(not(feature = "hydrate"))]
+fn track_active_heading(_ids: Vec<String>, _set_active: WriteSignal<Option<String>>) {}
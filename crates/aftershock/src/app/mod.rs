@@ -1,17 +1,25 @@
 use leptos::prelude::*;
-use leptos_meta::{provide_meta_context, MetaTags, Stylesheet, Title};
+use leptos_meta::{provide_meta_context, Meta, MetaTags, Stylesheet, Title};
 use leptos_router::{
     components::{Route, Router, Routes},
     path, Lazy,
 };
 use pages::{
-    about_page::AboutPageRoute, error_page::ErrorPage, home_page::HomePageRoute,
+    custom_page::CustomPageRoute, error_page::ErrorPage, home_page::HomePageRoute,
     main_page::MainPage,
 };
 
 use crate::{
-    app::pages::{archive_page::ArchivePageRoute, post_page::PostPageRoute},
-    MSG_DATA_NOT_FOUND, TITLE,
+    app::components::Theme,
+    app::pages::{
+        archive_page::ArchivePageRoute,
+        date_archive_page::{ArchiveOverviewRoute, DateArchivePageRoute},
+        post_page::PostPageRoute,
+        preview_page::PreviewPageRoute,
+        search_page::SearchPageRoute,
+        tag_index_page::TagIndexRoute,
+    },
+    config::{provide_site_config, use_site_config, SiteConfig},
 };
 
 mod components;
@@ -19,20 +27,33 @@ mod pages;
 mod server;
 
 pub fn shell(options: LeptosOptions) -> impl IntoView {
+    document(options, true, false)
+}
+
+/// The shell of a static export, hydrated or not. Its links load the next page from the
+/// export instead of calling server functions.
+pub fn static_shell(options: LeptosOptions, hydrate: bool) -> impl IntoView {
+    document(options, hydrate, true)
+}
+
+fn document(options: LeptosOptions, hydrate: bool, static_export: bool) -> impl IntoView {
     let aftershock_version = env!("CARGO_PKG_VERSION");
+    let language = SiteConfig::current().language.clone();
+    // Set before anything is painted, so a chosen theme never flashes the other one
+    let theme = Theme::current().class();
 
     view! {
         <!DOCTYPE html>
-        <html lang="zh-CN">
+        <html lang=language class=theme>
             <head>
                 <meta charset="utf-8" />
                 <meta name="viewport" content="width=device-width, initial-scale=1" />
                 <meta name="generator" content=format!("aftershock v{aftershock_version}")/>
                 <AutoReload options=options.clone() />
-                <HydrationScripts options />
+                {hydrate.then(|| view! { <HydrationScripts options /> })}
                 <MetaTags />
             </head>
-            <body class="bg-site-bg dark:bg-stone-800">
+            <body class="bg-site-bg dark:bg-stone-800" data-static-export=static_export.then_some("")>
                 <App />
             </body>
         </html>
@@ -42,12 +63,15 @@ pub fn shell(options: LeptosOptions) -> impl IntoView {
 #[component]
 pub fn App() -> impl IntoView {
     provide_meta_context();
-    let (error_msg, _) = signal(String::from(MSG_DATA_NOT_FOUND));
+    provide_site_config();
+    let config = use_site_config();
+    let (error_msg, _) = signal(config.messages.data_not_found);
 
     view! {
         <Stylesheet id="leptos" href="/pkg/aftershock.css" />
 
-        <Title text=TITLE />
+        <Title text=config.title />
+        <Meta name="author" content=config.author />
 
         <Router>
             <MainPage>
@@ -55,9 +79,26 @@ pub fn App() -> impl IntoView {
                     view! { <ErrorPage msg=error_msg /> }
                 }>
                     <Route path=path!("/") view={Lazy::<HomePageRoute>::new()} />
-                    <Route path=path!("/about") view={Lazy::<AboutPageRoute>::new()} />
+                    <Route path=path!("/page/:n") view={Lazy::<HomePageRoute>::new()} />
                     <Route path=path!("/posts/:uid") view={Lazy::<PostPageRoute>::new()} />
+                    <Route path=path!("/tags") view={Lazy::<TagIndexRoute>::new()} />
                     <Route path=path!("/tags/:tag") view={Lazy::<ArchivePageRoute>::new()} />
+                    <Route
+                        path=path!("/tags/:tag/page/:n")
+                        view={Lazy::<ArchivePageRoute>::new()}
+                    />
+                    <Route path=path!("/archive") view={Lazy::<ArchiveOverviewRoute>::new()} />
+                    <Route
+                        path=path!("/archive/:year")
+                        view={Lazy::<DateArchivePageRoute>::new()}
+                    />
+                    <Route
+                        path=path!("/archive/:year/:month")
+                        view={Lazy::<DateArchivePageRoute>::new()}
+                    />
+                    <Route path=path!("/search") view={Lazy::<SearchPageRoute>::new()} />
+                    <Route path=path!("/preview/:token") view={Lazy::<PreviewPageRoute>::new()} />
+                    <Route path=path!("/:slug") view={Lazy::<CustomPageRoute>::new()} />
                 </Routes>
             </MainPage>
         </Router>
@@ -6,67 +6,81 @@ use leptos_router::{
     LazyRoute,
 };
 
-use crate::{
-    app::{
-        components::{MessageBox, PostMetaListGroupByTag},
-        server::get_posts_meta_by_tag,
-    },
-    MSG_ARCHIVE_PLACEHOLDER,
+use crate::app::{
+    components::{ErrorMessage, Pagination, PostMetaListGroupByTag},
+    server::{get_posts_meta_by_tag_page, AppError},
 };
 
 #[derive(Params, PartialEq)]
 struct TagParams {
     tag: Option<String>,
+    n: Option<i64>,
 }
 
 pub struct ArchivePageRoute {
     params: Memo<Result<TagParams, ParamsError>>,
-    data: Resource<Result<Vec<aftershock_bridge::PostMeta>, ()>>,
-    msg: ReadSignal<String>,
+    data: Resource<Result<aftershock_bridge::Paged<aftershock_bridge::PostMeta>, AppError>>,
 }
 
 #[lazy_route]
 impl LazyRoute for ArchivePageRoute {
     fn data() -> Self {
         let params = use_params::<TagParams>();
-        let (msg, _set_msg) = signal(String::from(MSG_ARCHIVE_PLACEHOLDER));
 
-        let data = Resource::new(
-            move || params.read().as_ref().ok().and_then(|p| p.tag.clone()),
-            |tag| async move {
-                match tag {
-                    None => Err(()),
-                    Some(ref empty) if empty.is_empty() => Err(()),
-                    Some(tag) => get_posts_meta_by_tag(tag).await.map_err(|_| ()),
+        let data = Resource::new_blocking(
+            move || {
+                params
+                    .read()
+                    .as_ref()
+                    .ok()
+                    .map(|p| (p.tag.clone(), p.n.unwrap_or(1)))
+            },
+            |params| async move {
+                match params {
+                    None => Err(AppError::BadRequest("Invalid page".into())),
+                    Some((Some(tag), page)) if !tag.is_empty() => {
+                        get_posts_meta_by_tag_page(tag, page).await
+                    }
+                    Some(_) => Err(AppError::NotFound),
                 }
             },
         );
 
-        Self { params, data, msg }
+        Self { params, data }
     }
 
     fn view(this: Self) -> AnyView {
-        let ArchivePageRoute { params, data, msg } = this;
+        let ArchivePageRoute { params, data } = this;
         view! {
             <Suspense>
                 {move || {
                     data.get()
                         .map(|result| match result {
-                            Ok(posts) => {
+                            // A tag nothing is tagged with does not exist
+                            Ok(paged) if paged.items.is_empty() => {
+                                view! { <ErrorMessage error=AppError::NotFound /> }.into_any()
+                            }
+                            Ok(paged) => {
+                                let tag = params
+                                    .read()
+                                    .as_ref()
+                                    .ok()
+                                    .and_then(|p| p.tag.clone())
+                                    .unwrap_or_default();
                                 view! {
                                     <PostMetaListGroupByTag
-                                        post_meta_list=posts.clone()
-                                        primary_tag=format!("#{}", params
-                                            .read()
-                                            .as_ref()
-                                            .ok()
-                                            .and_then(|p| p.tag.clone())
-                                            .unwrap_or_default())
+                                        post_meta_list=paged.items
+                                        primary_tag=format!("#{tag}")
+                                    />
+                                    <Pagination
+                                        current=paged.page
+                                        total_pages=paged.total_pages
+                                        base=format!("/tags/{tag}")
                                     />
                                 }
                                     .into_any()
                             }
-                            Err(_) => view! { <MessageBox msg=msg /> }.into_any(),
+                            Err(error) => view! { <ErrorMessage error=error /> }.into_any(),
                         })
                 }}
             </Suspense>
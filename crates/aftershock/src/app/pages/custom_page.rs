@@ -0,0 +1,69 @@
+use leptos::prelude::*;
+use leptos_meta::Title;
+use leptos_router::{hooks::use_params, lazy_route, params::Params, LazyRoute};
+
+use crate::{
+    app::{
+        components::{ContentSerif, ErrorMessage, PostHead, ProseContent},
+        server::{get_page, AppError},
+    },
+    config::use_site_config,
+};
+
+#[derive(Params, PartialEq)]
+struct PageParams {
+    pub slug: Option<String>,
+}
+
+/// Any page created with `aftershock_cli page add`, addressed by its uid.
+pub struct CustomPageRoute {
+    data: Resource<Result<aftershock_bridge::Post, AppError>>,
+}
+
+#[lazy_route]
+impl LazyRoute for CustomPageRoute {
+    fn data() -> Self {
+        let params = use_params::<PageParams>();
+
+        let data = Resource::new_blocking(
+            move || params.read().as_ref().ok().and_then(|p| p.slug.clone()),
+            |slug| async move {
+                match slug {
+                    Some(slug) if !slug.is_empty() => get_page(slug).await,
+                    _ => Err(AppError::NotFound),
+                }
+            },
+        );
+
+        Self { data }
+    }
+
+    fn view(this: Self) -> AnyView {
+        let CustomPageRoute { data } = this;
+        let site_title = use_site_config().title;
+
+        view! {
+            <Suspense>
+                {move || {
+                    data.get()
+                        .map(|result| {
+                            match result {
+                                Ok(page) => {
+                                    view! {
+                                        <Title text=format!("{} - {}", page.title, site_title) />
+                                        <PostHead post=page.clone() />
+                                        <ContentSerif>
+                                            <ProseContent body=page.body />
+                                        </ContentSerif>
+                                    }
+                                        .into_any()
+                                }
+                                Err(error) => view! { <ErrorMessage error=error /> }.into_any(),
+                            }
+                        })
+                }}
+            </Suspense>
+        }
+        .into_any()
+    }
+}
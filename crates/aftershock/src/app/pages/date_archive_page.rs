@@ -0,0 +1,172 @@
+use leptos::prelude::*;
+use leptos_meta::Title;
+use leptos_router::{components::A, hooks::use_params, lazy_route, params::Params, LazyRoute};
+
+use crate::{
+    app::{
+        components::{ErrorMessage, PostMetaSection},
+        server::{get_archive_overview, get_posts_meta_by_period, AppError},
+    },
+    config::use_site_config,
+    utils::{
+        datetime::{use_time_settings, AppDateTime},
+        group_by,
+    },
+};
+
+/// Every year and month that has posts, with their counts, newest first.
+pub struct ArchiveOverviewRoute {
+    data: Resource<Result<Vec<aftershock_bridge::ArchiveMonth>, AppError>>,
+}
+
+#[lazy_route]
+impl LazyRoute for ArchiveOverviewRoute {
+    fn data() -> Self {
+        let data = Resource::new_blocking(|| (), |_| async move { get_archive_overview().await });
+
+        Self { data }
+    }
+
+    fn view(this: Self) -> AnyView {
+        let ArchiveOverviewRoute { data } = this;
+        let config = use_site_config();
+        let locale = use_time_settings().locale;
+
+        view! {
+            <Title text=format!("{} - {}", config.messages.archive, config.title) />
+            <Suspense>
+                {move || {
+                    data.get()
+                        .map(|result| match result {
+                            Ok(months) => {
+                                let years = group_by(months, |x| x.year, |x| x.clone());
+                                let mut years = years.into_iter().collect::<Vec<_>>();
+                                years.sort_unstable_by_key(|x| std::cmp::Reverse(x.0));
+                                view! {
+                                    <div class="flex flex-col gap-4 font-af-serif">
+                                        {years
+                                            .into_iter()
+                                            .map(|(year, mut months)| {
+                                                months.sort_unstable_by_key(|x| std::cmp::Reverse(x.month));
+                                                let total: i64 = months.iter().map(|x| x.count).sum();
+                                                view! {
+                                                    <section class="flex flex-col gap-2">
+                                                        <h1 class="font-bold text-4xl">
+                                                            <A href=format!("/archive/{year}")>{year}</A>
+                                                            <span class="ml-4 text-xl font-medium">{total}</span>
+                                                        </h1>
+                                                        <ul class="flex flex-row flex-wrap gap-x-6 gap-y-2 font-semibold">
+                                                            {months
+                                                                .into_iter()
+                                                                .map(|x| {
+                                                                    view! {
+                                                                        <li>
+                                                                            <A href=format!("/archive/{year}/{}", x.month)>
+                                                                                {locale.month_name(x.month)}
+                                                                            </A>
+                                                                            <span class="ml-2 font-medium">{x.count}</span>
+                                                                        </li>
+                                                                    }
+                                                                })
+                                                                .collect_view()}
+                                                        </ul>
+                                                    </section>
+                                                }
+                                            })
+                                            .collect_view()}
+                                    </div>
+                                }
+                                    .into_any()
+                            }
+                            Err(error) => view! { <ErrorMessage error=error /> }.into_any(),
+                        })
+                }}
+            </Suspense>
+        }
+        .into_any()
+    }
+}
+
+#[derive(Params, PartialEq)]
+struct PeriodParams {
+    year: Option<i32>,
+    month: Option<u32>,
+}
+
+/// Posts of one year, `/archive/:year`, or one month, `/archive/:year/:month`, in the
+/// site timezone.
+pub struct DateArchivePageRoute {
+    params: Memo<Option<(i32, Option<u32>)>>,
+    data: Resource<Result<Vec<aftershock_bridge::PostMeta>, AppError>>,
+}
+
+#[lazy_route]
+impl LazyRoute for DateArchivePageRoute {
+    fn data() -> Self {
+        let params = use_params::<PeriodParams>();
+        let params = Memo::new(move |_| {
+            let params = params.read();
+            let params = params.as_ref().ok()?;
+            Some((params.year?, params.month))
+        });
+
+        let data = Resource::new_blocking(
+            move || params.get(),
+            |period| async move {
+                match period {
+                    Some((year, month)) => get_posts_meta_by_period(year, month).await,
+                    None => Err(AppError::BadRequest("Invalid period".into())),
+                }
+            },
+        );
+
+        Self { params, data }
+    }
+
+    fn view(this: Self) -> AnyView {
+        let DateArchivePageRoute { params, data } = this;
+        let site_title = use_site_config().title;
+        let settings = use_time_settings();
+
+        view! {
+            <Suspense>
+                {move || {
+                    data.get()
+                        .map(|result| match (result, params.get()) {
+                            (Ok(posts), Some((year, month))) if !posts.is_empty() => {
+                                let section_title = match month {
+                                    Some(month) => settings.locale.format_year_month(year, month),
+                                    None => year.to_string(),
+                                };
+                                let posts = posts
+                                    .into_iter()
+                                    .map(|post| {
+                                        let time = AppDateTime::from_timestamp(
+                                            post.created_at,
+                                            settings.timezone,
+                                            settings.locale,
+                                        );
+                                        (time, post)
+                                    })
+                                    .collect();
+                                view! {
+                                    <Title text=format!("{section_title} - {site_title}") />
+                                    <div class="flex flex-col gap-4 font-af-serif">
+                                        <PostMetaSection
+                                            section_title=section_title
+                                            post_meta_list=posts
+                                            with_summary=false
+                                        />
+                                    </div>
+                                }
+                                    .into_any()
+                            }
+                            (Err(error), _) => view! { <ErrorMessage error=error /> }.into_any(),
+                            _ => view! { <ErrorMessage error=AppError::NotFound /> }.into_any(),
+                        })
+                }}
+            </Suspense>
+        }
+        .into_any()
+    }
+}
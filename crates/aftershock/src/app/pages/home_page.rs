@@ -1,23 +1,36 @@
 use leptos::prelude::*;
-use leptos_router::{lazy_route, LazyRoute};
+use leptos_router::{hooks::use_params, lazy_route, params::Params, LazyRoute};
 
-use crate::app::components::{MessageBox, PostMetaListGroupByTime};
-use crate::app::server::get_published_posts_meta;
-use crate::MSG_LOAD_DATA_FAILURE;
+use crate::app::components::{
+    page_url, ErrorMessage, MessageBox, Pagination, PostMetaListGroupByTime, SiteHead,
+};
+use crate::app::server::{get_published_posts_meta_page, AppError};
+use crate::config::use_site_config;
+
+#[derive(Params, PartialEq)]
+struct PageParams {
+    n: Option<i64>,
+}
 
 pub struct HomePageRoute {
-    data: Resource<Result<Vec<aftershock_bridge::PostMeta>, ()>>,
+    data: Resource<Result<aftershock_bridge::Paged<aftershock_bridge::PostMeta>, AppError>>,
     msg: ReadSignal<String>,
 }
 
 #[lazy_route]
 impl LazyRoute for HomePageRoute {
     fn data() -> Self {
-        let (msg, _) = signal(String::from(MSG_LOAD_DATA_FAILURE));
+        let params = use_params::<PageParams>();
+        let (msg, _) = signal(use_site_config().messages.data_not_found);
 
-        let data = Resource::new(
-            || (),
-            |_| async move { get_published_posts_meta().await.map_err(|_| ()) },
+        let data = Resource::new_blocking(
+            move || params.read().as_ref().map(|p| p.n.unwrap_or(1)).ok(),
+            |page| async move {
+                match page {
+                    Some(page) => get_published_posts_meta_page(page).await,
+                    None => Err(AppError::BadRequest("Invalid page".into())),
+                }
+            },
         );
 
         Self { data, msg }
@@ -31,17 +44,23 @@ impl LazyRoute for HomePageRoute {
                 {move || {
                     data.get()
                         .map(|result| match result {
-                            Ok(posts) if !posts.is_empty() => {
+                            Ok(paged) if !paged.items.is_empty() => {
                                 view! {
+                                    <SiteHead path=page_url("/", paged.page) />
                                     <PostMetaListGroupByTime
-                                        post_meta_list=posts.clone()
+                                        post_meta_list=paged.items
                                         with_summary=true
                                     />
+                                    <Pagination
+                                        current=paged.page
+                                        total_pages=paged.total_pages
+                                        base="/".to_string()
+                                    />
                                 }
                                     .into_any()
                             }
                             Ok(_) => view! { <MessageBox msg=msg /> }.into_any(),
-                            Err(_) => view! { <MessageBox msg=msg /> }.into_any(),
+                            Err(error) => view! { <ErrorMessage error=error /> }.into_any(),
                         })
                 }}
             </Suspense>
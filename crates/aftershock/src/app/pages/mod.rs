@@ -1,6 +1,10 @@
-pub mod about_page;
 pub mod archive_page;
+pub mod custom_page;
+pub mod date_archive_page;
 pub mod error_page;
 pub mod home_page;
 pub mod main_page;
 pub mod post_page;
+pub mod preview_page;
+pub mod search_page;
+pub mod tag_index_page;
@@ -1,9 +1,6 @@
-use crate::{
-    app::{
-        components::{MessageBox, Post},
-        server::get_post_by_uid,
-    },
-    MSG_LOAD_DATA_FAILURE,
+use crate::app::{
+    components::{ErrorMessage, Post, PostHead, PostNeighbors, ProtectedPost},
+    server::{get_post_by_uid, get_post_neighbors, AppError},
 };
 use leptos::prelude::*;
 use leptos_router::{hooks::use_params, lazy_route, params::Params, LazyRoute};
@@ -14,43 +11,74 @@ struct PostParams {
 }
 
 pub struct PostPageRoute {
-    data: Resource<Result<aftershock_bridge::Post, ()>>,
-    msg: ReadSignal<String>,
+    data: Resource<Result<aftershock_bridge::Post, AppError>>,
+    neighbors: Resource<Option<aftershock_bridge::PostNeighbors>>,
 }
 
 #[lazy_route]
 impl LazyRoute for PostPageRoute {
     fn data() -> Self {
         let params = use_params::<PostParams>();
-        let (msg, _) = signal(String::from(MSG_LOAD_DATA_FAILURE));
 
-        let data = Resource::new(
+        // Blocking, so the status and head of the response follow the post
+        let data = Resource::new_blocking(
             move || params.read().as_ref().ok().and_then(|p| p.uid.clone()),
             |uid| async move {
                 match uid {
-                    None => Err(()),
-                    Some(empty) if empty.is_empty() => Err(()),
-                    Some(uid) => get_post_by_uid(uid).await.map_err(|_| ()),
+                    Some(uid) if !uid.is_empty() => get_post_by_uid(uid).await,
+                    _ => Err(AppError::NotFound),
                 }
             },
         );
 
-        Self { data, msg }
+        // Fetched alongside the post, it never holds the post back
+        let neighbors = Resource::new(
+            move || params.read().as_ref().ok().and_then(|p| p.uid.clone()),
+            |uid| async move {
+                match uid {
+                    Some(uid) if !uid.is_empty() => get_post_neighbors(uid).await.ok(),
+                    _ => None,
+                }
+            },
+        );
+
+        Self { data, neighbors }
     }
 
     fn view(this: Self) -> AnyView {
-        let PostPageRoute { data, msg, .. } = this;
+        let PostPageRoute { data, neighbors } = this;
 
         view! {
             <Suspense>
                 {move || {
                     data.get()
                         .map(|result| match result {
-                            Ok(post) => view! { <Post post=post.clone() /> }.into_any(),
-                            Err(_) => view! { <MessageBox msg=msg /> }.into_any(),
+                            Ok(post) if post.visibility == "protected" && post.body.is_empty() => {
+                                view! {
+                                    <PostHead post=post.clone() />
+                                    <ProtectedPost post=post.clone() />
+                                }
+                                    .into_any()
+                            }
+                            Ok(post) => {
+                                view! {
+                                    <PostHead post=post.clone() />
+                                    <Post post=post.clone() />
+                                }
+                                    .into_any()
+                            }
+                            Err(error) => view! { <ErrorMessage error=error /> }.into_any(),
                         })
                 }}
             </Suspense>
+            <Suspense>
+                {move || {
+                    neighbors
+                        .get()
+                        .flatten()
+                        .map(|neighbors| view! { <PostNeighbors neighbors=neighbors /> })
+                }}
+            </Suspense>
         }
         .into_any()
     }
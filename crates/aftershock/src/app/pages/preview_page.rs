@@ -0,0 +1,69 @@
+use crate::{
+    app::{
+        components::{ContentSans, ErrorMessage, Post},
+        server::{get_preview, AppError},
+    },
+    config::use_site_config,
+};
+use leptos::prelude::*;
+use leptos_meta::Meta;
+use leptos_router::{hooks::use_params, lazy_route, params::Params, LazyRoute};
+
+#[derive(Params, PartialEq)]
+struct PreviewParams {
+    pub token: Option<String>,
+}
+
+pub struct PreviewPageRoute {
+    data: Resource<Result<aftershock_bridge::Post, AppError>>,
+}
+
+#[lazy_route]
+impl LazyRoute for PreviewPageRoute {
+    fn data() -> Self {
+        let params = use_params::<PreviewParams>();
+
+        let data = Resource::new_blocking(
+            move || params.read().as_ref().ok().and_then(|p| p.token.clone()),
+            |token| async move {
+                match token {
+                    Some(token) if !token.is_empty() => get_preview(token).await,
+                    _ => Err(AppError::NotFound),
+                }
+            },
+        );
+
+        Self { data }
+    }
+
+    fn view(this: Self) -> AnyView {
+        let PreviewPageRoute { data } = this;
+        let banner = use_site_config().messages.draft_preview;
+
+        view! {
+            // Previews are shared by link only, keep them out of search engines
+            <Meta name="robots" content="noindex, nofollow" />
+            <Suspense>
+                {move || {
+                    data.get()
+                        .map(|result| match result {
+                            Ok(post) => {
+                                let banner = banner.clone();
+                                view! {
+                                    <div class="border-2 border-dashed border-site-dark px-4 py-2 mb-5 rounded-lg">
+                                        <ContentSans>
+                                            <p>{banner}</p>
+                                        </ContentSans>
+                                    </div>
+                                    <Post post=post.clone() />
+                                }
+                                    .into_any()
+                            }
+                            Err(error) => view! { <ErrorMessage error=error /> }.into_any(),
+                        })
+                }}
+            </Suspense>
+        }
+        .into_any()
+    }
+}
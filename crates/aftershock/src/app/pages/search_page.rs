@@ -0,0 +1,102 @@
+use std::time::Duration;
+
+use leptos::prelude::*;
+use leptos_meta::{Meta, Title};
+use leptos_router::{
+    components::Form,
+    hooks::{use_navigate, use_query_map},
+    lazy_route,
+    location::Url,
+    LazyRoute, NavigateOptions,
+};
+
+use crate::{
+    app::{
+        components::{ErrorMessage, MessageBox, SearchHitList},
+        server::{search_posts, AppError},
+    },
+    config::use_site_config,
+};
+
+/// How long typing has to pause before the results follow
+const SEARCH_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Results for `/search?q=`. The URL is the only state: the form submits to it without
+/// JavaScript, and once hydrated, typing replaces it after a short pause.
+pub struct SearchPageRoute {
+    query: Memo<String>,
+    data: Resource<Result<Vec<aftershock_bridge::SearchHit>, AppError>>,
+}
+
+#[lazy_route]
+impl LazyRoute for SearchPageRoute {
+    fn data() -> Self {
+        let query_map = use_query_map();
+        let query = Memo::new(move |_| query_map.read().get("q").unwrap_or_default());
+
+        let data = Resource::new_blocking(
+            move || query.get(),
+            |query| async move {
+                match query.trim() {
+                    "" => Ok(vec![]),
+                    _ => search_posts(query).await,
+                }
+            },
+        );
+
+        Self { query, data }
+    }
+
+    fn view(this: Self) -> AnyView {
+        let SearchPageRoute { query, data } = this;
+        let config = use_site_config();
+        let (no_results, _) = signal(config.messages.search_no_results);
+        let placeholder = config.messages.search_placeholder;
+        let site_title = config.title;
+
+        let navigate = use_navigate();
+        let mut on_input = debounce(SEARCH_DEBOUNCE, move |query: String| {
+            navigate(
+                &format!("/search?q={}", Url::escape(&query)),
+                NavigateOptions {
+                    replace: true,
+                    scroll: false,
+                    ..Default::default()
+                },
+            );
+        });
+
+        view! {
+            <Title text=move || match query.read().trim() {
+                "" => site_title.clone(),
+                query => format!("{query} - {site_title}"),
+            } />
+            <Meta name="robots" content="noindex" />
+            <Form action="/search" method="get" attr:role="search" attr:class="mb-6">
+                <input
+                    type="search"
+                    name="q"
+                    value=move || query.get()
+                    placeholder=placeholder.clone()
+                    aria-label=placeholder
+                    on:input=move |ev| on_input(event_target_value(&ev))
+                    class="w-full border-b-2 border-site-dark bg-transparent px-1 py-2 text-xl font-af-serif focus:outline-none"
+                />
+            </Form>
+            <Transition>
+                {move || {
+                    data.get()
+                        .map(|result| match result {
+                            Ok(hits) if !hits.is_empty() => {
+                                view! { <SearchHitList hits=hits /> }.into_any()
+                            }
+                            Ok(_) if query.read().trim().is_empty() => ().into_any(),
+                            Ok(_) => view! { <MessageBox msg=no_results /> }.into_any(),
+                            Err(error) => view! { <ErrorMessage error=error /> }.into_any(),
+                        })
+                }}
+            </Transition>
+        }
+        .into_any()
+    }
+}
@@ -0,0 +1,167 @@
+use leptos::prelude::*;
+use leptos_meta::Title;
+use leptos_router::{components::A, hooks::use_query_map, lazy_route, LazyRoute};
+
+use crate::{
+    app::{
+        components::ErrorMessage,
+        server::{get_tag_counts, AppError},
+    },
+    config::use_site_config,
+};
+
+#[derive(Clone, Copy, PartialEq)]
+enum TagSort {
+    Name,
+    Count,
+}
+
+/// Every tag of the published posts, as a list or a cloud weighted by post count. Sorting
+/// and layout live in the query string, `?sort=count&view=cloud`, so plain links switch them.
+pub struct TagIndexRoute {
+    sort: Memo<TagSort>,
+    cloud: Memo<bool>,
+    data: Resource<Result<Vec<aftershock_bridge::TagCount>, AppError>>,
+}
+
+#[lazy_route]
+impl LazyRoute for TagIndexRoute {
+    fn data() -> Self {
+        let query = use_query_map();
+        let sort = Memo::new(move |_| match query.read().get("sort").as_deref() {
+            Some("count") => TagSort::Count,
+            _ => TagSort::Name,
+        });
+        let cloud = Memo::new(move |_| query.read().get("view").as_deref() == Some("cloud"));
+
+        let data = Resource::new_blocking(|| (), |_| async move { get_tag_counts().await });
+
+        Self { sort, cloud, data }
+    }
+
+    fn view(this: Self) -> AnyView {
+        let TagIndexRoute { sort, cloud, data } = this;
+        let config = use_site_config();
+        let messages = config.messages.clone();
+
+        let href = move |sort: TagSort, cloud: bool| {
+            let sort = match sort {
+                TagSort::Name => "name",
+                TagSort::Count => "count",
+            };
+            let view = match cloud {
+                true => "cloud",
+                false => "list",
+            };
+            format!("/tags?sort={sort}&view={view}")
+        };
+        let option = move |label: String, selected: bool, href: String| {
+            view! {
+                <A href=href attr:aria-current=selected.then_some("page") attr:class=match selected {
+                    true => "underline",
+                    false => "",
+                }>
+                    {label}
+                </A>
+            }
+        };
+
+        view! {
+            <Title text=format!("{} - {}", config.messages.tag_index, config.title) />
+            <section class="flex flex-col gap-4 font-af-serif">
+                <h1 class="font-bold text-4xl">{config.messages.tag_index}</h1>
+                <nav class="flex flex-row flex-wrap gap-4 font-semibold">
+                    {move || {
+                        let (sort, cloud) = (sort.get(), cloud.get());
+                        view! {
+                            {option(
+                                messages.sort_by_name.clone(),
+                                sort == TagSort::Name,
+                                href(TagSort::Name, cloud),
+                            )}
+                            {option(
+                                messages.sort_by_count.clone(),
+                                sort == TagSort::Count,
+                                href(TagSort::Count, cloud),
+                            )}
+                            <span>"|"</span>
+                            {option(messages.tag_list.clone(), !cloud, href(sort, false))}
+                            {option(messages.tag_cloud.clone(), cloud, href(sort, true))}
+                        }
+                    }}
+                </nav>
+                <Suspense>
+                    {move || {
+                        data.get()
+                            .map(|result| match result {
+                                Ok(mut tags) => {
+                                    if sort.get() == TagSort::Count {
+                                        tags.sort_by_key(|x| std::cmp::Reverse(x.count));
+                                    }
+                                    match cloud.get() {
+                                        true => view! { <TagCloud tags=tags /> }.into_any(),
+                                        false => view! { <TagCountList tags=tags /> }.into_any(),
+                                    }
+                                }
+                                Err(error) => view! { <ErrorMessage error=error /> }.into_any(),
+                            })
+                    }}
+                </Suspense>
+            </section>
+        }
+        .into_any()
+    }
+}
+
+#[component]
+fn TagCountList(tags: Vec<aftershock_bridge::TagCount>) -> impl IntoView {
+    view! {
+        <ul class="flex flex-col gap-2 font-semibold">
+            {tags
+                .into_iter()
+                .map(|x| {
+                    view! {
+                        <li class="flex flex-row gap-4">
+                            <A href=format!("/tags/{}", x.tag)>#{x.tag}</A>
+                            <span class="font-medium">{x.count}</span>
+                        </li>
+                    }
+                })
+                .collect_view()}
+        </ul>
+    }
+}
+
+/// Font size of a tag in the cloud, from 1em for the rarest to 2.5em for the most used.
+fn cloud_weight(count: i64, min: i64, max: i64) -> f64 {
+    if max <= min {
+        return 1.0;
+    }
+    // Logarithmic, so one huge tag doesn't shrink everything else to the minimum
+    let scale = ((count - min + 1) as f64).ln() / ((max - min + 1) as f64).ln();
+    1.0 + 1.5 * scale
+}
+
+#[component]
+fn TagCloud(tags: Vec<aftershock_bridge::TagCount>) -> impl IntoView {
+    let min = tags.iter().map(|x| x.count).min().unwrap_or_default();
+    let max = tags.iter().map(|x| x.count).max().unwrap_or_default();
+
+    view! {
+        <ul class="flex flex-row flex-wrap items-baseline gap-x-4 gap-y-2 font-semibold">
+            {tags
+                .into_iter()
+                .map(|x| {
+                    let style = format!("font-size: {:.2}em", cloud_weight(x.count, min, max));
+                    view! {
+                        <li style=style>
+                            <A href=format!("/tags/{}", x.tag) attr:title=x.count.to_string()>
+                                #{x.tag}
+                            </A>
+                        </li>
+                    }
+                })
+                .collect_view()}
+        </ul>
+    }
+}
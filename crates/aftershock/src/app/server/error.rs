@@ -0,0 +1,92 @@
+use leptos::server_fn::{
+    codec::JsonEncoding,
+    error::{FromServerFnError, ServerFnErrorErr},
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Why a server function could not deliver, kept apart so a page can answer with the
+/// matching status and message instead of one failure for everything.
+#[derive(Error, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AppError {
+    #[error("Not found")]
+    NotFound,
+    #[error("Bad request: {0}")]
+    BadRequest(String),
+    #[error("Forbidden")]
+    Forbidden,
+    /// Storage could not be reached, or failed on its side
+    #[error("Storage unavailable: {0}")]
+    Unavailable(String),
+    /// Storage answered with something that is not what was asked for
+    #[error("Invalid response from storage: {0}")]
+    InvalidResponse(String),
+    #[error("{0}")]
+    ServerFn(ServerFnErrorErr),
+}
+
+impl FromServerFnError for AppError {
+    type Encoder = JsonEncoding;
+
+    fn from_server_fn_error(value: ServerFnErrorErr) -> Self {
+        AppError::ServerFn(value)
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl AppError {
+    pub fn status(&self) -> axum::http::StatusCode {
+        use axum::http::StatusCode;
+
+        match self {
+            AppError::NotFound => StatusCode::NOT_FOUND,
+            AppError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            AppError::Forbidden => StatusCode::FORBIDDEN,
+            AppError::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            AppError::InvalidResponse(_) => StatusCode::BAD_GATEWAY,
+            AppError::ServerFn(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl From<reqwest::Error> for AppError {
+    fn from(value: reqwest::Error) -> Self {
+        match value.is_decode() {
+            true => AppError::InvalidResponse(value.to_string()),
+            false => AppError::Unavailable(value.to_string()),
+        }
+    }
+}
+
+/// GET `url` from storage and decode its JSON, see [`json`].
+#[cfg(feature = "ssr")]
+pub async fn get_json<T: serde::de::DeserializeOwned>(
+    url: impl reqwest::IntoUrl,
+) -> Result<T, AppError> {
+    json(reqwest::get(url).await?).await
+}
+
+/// Decode the JSON of a storage response, telling a missing resource and a rejected
+/// request apart from storage being down.
+#[cfg(feature = "ssr")]
+pub async fn json<T: serde::de::DeserializeOwned>(
+    response: reqwest::Response,
+) -> Result<T, AppError> {
+    use reqwest::StatusCode;
+
+    match response.status() {
+        status if status.is_success() => Ok(response.json::<T>().await?),
+        StatusCode::NOT_FOUND => Err(AppError::NotFound),
+        StatusCode::FORBIDDEN => Err(AppError::Forbidden),
+        StatusCode::BAD_REQUEST => Err(AppError::BadRequest(
+            response.text().await.unwrap_or_default(),
+        )),
+        status if status.is_server_error() => {
+            Err(AppError::Unavailable(format!("storage answered {status}")))
+        }
+        status => Err(AppError::InvalidResponse(format!(
+            "storage answered {status}"
+        ))),
+    }
+}
@@ -1,46 +1,139 @@
 use leptos::prelude::*;
 
+mod error;
+
+pub use error::AppError;
 #[cfg(feature = "ssr")]
-static API_BASE: &'static str = "http://127.0.0.1:3030/api/v1";
+use error::{get_json, json};
+
+/// The storage API base with `segments` appended, each percent-encoded, so a tag or uid
+/// can't reach another endpoint.
+#[cfg(feature = "ssr")]
+fn api_url(segments: &[&str]) -> Result<reqwest::Url, AppError> {
+    let base = &crate::config::SiteConfig::current().api_base;
+    let mut url = reqwest::Url::parse(base).map_err(|e| AppError::Unavailable(e.to_string()))?;
+    url.path_segments_mut()
+        .map_err(|_| AppError::Unavailable(format!("{base} is not a base URL")))?
+        .pop_if_empty()
+        .extend(segments);
+    Ok(url)
+}
 
 #[server]
-pub async fn get_published_posts_meta() -> Result<Vec<aftershock_bridge::PostMeta>, ServerFnError> {
-    let url = format!("{API_BASE}/posts/meta");
-    let meta = reqwest::get(url)
-        .await?
-        .json::<Vec<aftershock_bridge::PostMeta>>()
-        .await?;
+pub async fn get_published_posts_meta_page(
+    page: i64,
+) -> Result<aftershock_bridge::Paged<aftershock_bridge::PostMeta>, AppError> {
+    let per_page = crate::config::SiteConfig::current().page_size;
+    let mut url = api_url(&["posts", "meta", "page", &page.to_string()])?;
+    url.query_pairs_mut()
+        .append_pair("per_page", &per_page.to_string());
+    let meta = get_json::<aftershock_bridge::Paged<aftershock_bridge::PostMeta>>(url).await?;
     Ok(meta)
 }
 
 #[server]
-pub async fn get_post_by_uid(uid: String) -> Result<aftershock_bridge::Post, ServerFnError> {
-    let url = format!("{API_BASE}/posts/uid/{uid}");
-    let post = reqwest::get(url)
-        .await?
-        .json::<aftershock_bridge::Post>()
-        .await?;
+pub async fn get_tag_counts() -> Result<Vec<aftershock_bridge::TagCount>, AppError> {
+    let url = api_url(&["posts", "tags"])?;
+    let tags = get_json::<Vec<aftershock_bridge::TagCount>>(url).await?;
+    Ok(tags)
+}
+
+#[server]
+pub async fn get_archive_overview() -> Result<Vec<aftershock_bridge::ArchiveMonth>, AppError> {
+    let tz = crate::config::SiteConfig::current().time.timezone.name();
+    let mut url = api_url(&["posts", "archive"])?;
+    url.query_pairs_mut().append_pair("tz", tz);
+    let months = get_json::<Vec<aftershock_bridge::ArchiveMonth>>(url).await?;
+    Ok(months)
+}
+
+/// Posts created in a year, or a month of it, of the site timezone.
+#[server]
+pub async fn get_posts_meta_by_period(
+    year: i32,
+    month: Option<u32>,
+) -> Result<Vec<aftershock_bridge::PostMeta>, AppError> {
+    let tz = crate::config::SiteConfig::current().time.timezone.name();
+    let (year, month) = (year.to_string(), month.map(|month| month.to_string()));
+    let mut url = match &month {
+        Some(month) => api_url(&["posts", "archive", &year, month])?,
+        None => api_url(&["posts", "archive", &year])?,
+    };
+    url.query_pairs_mut().append_pair("tz", tz);
+    let meta = get_json::<Vec<aftershock_bridge::PostMeta>>(url).await?;
+    Ok(meta)
+}
+
+#[server]
+pub async fn get_post_neighbors(uid: String) -> Result<aftershock_bridge::PostNeighbors, AppError> {
+    let url = api_url(&["posts", "uid", &uid, "neighbors"])?;
+    let neighbors = get_json::<aftershock_bridge::PostNeighbors>(url).await?;
+    Ok(neighbors)
+}
+
+#[server]
+pub async fn search_posts(query: String) -> Result<Vec<aftershock_bridge::SearchHit>, AppError> {
+    let mut url = api_url(&["posts", "search"])?;
+    url.query_pairs_mut().append_pair("q", &query);
+    let hits = get_json::<Vec<aftershock_bridge::SearchHit>>(url).await?;
+    Ok(hits)
+}
+
+#[server]
+pub async fn get_post_by_uid(uid: String) -> Result<aftershock_bridge::Post, AppError> {
+    let url = api_url(&["posts", "uid", &uid])?;
+    let post = get_json::<aftershock_bridge::Post>(url).await?;
     Ok(post)
 }
 
 #[server]
-pub async fn get_page(name: String) -> Result<aftershock_bridge::Post, ServerFnError> {
-    let url = format!("{API_BASE}/pages/uid/{name}");
-    let page = reqwest::get(url)
-        .await?
-        .json::<aftershock_bridge::Post>()
-        .await?;
+pub async fn get_page(name: String) -> Result<aftershock_bridge::Post, AppError> {
+    let url = api_url(&["pages", "uid", &name])?;
+    let page = get_json::<aftershock_bridge::Post>(url).await?;
     Ok(page)
 }
 
+/// Published pages that asked to be in the navigation, in order.
 #[server]
-pub async fn get_posts_meta_by_tag(
+pub async fn get_nav_pages() -> Result<Vec<aftershock_bridge::PostMeta>, AppError> {
+    let url = api_url(&["pages", "meta"])?;
+    let mut pages = get_json::<Vec<aftershock_bridge::PostMeta>>(url).await?;
+    pages.retain(|page| page.nav.is_some());
+    pages.sort_by_key(|page| page.nav.as_ref().map(|nav| nav.order));
+    Ok(pages)
+}
+
+#[server]
+pub async fn get_posts_meta_by_tag_page(
     tag: String,
-) -> Result<Vec<aftershock_bridge::PostMeta>, ServerFnError> {
-    let url = format!("{API_BASE}/posts/tag/{tag}");
-    let meta = reqwest::get(url)
-        .await?
-        .json::<Vec<aftershock_bridge::PostMeta>>()
-        .await?;
+    page: i64,
+) -> Result<aftershock_bridge::Paged<aftershock_bridge::PostMeta>, AppError> {
+    let per_page = crate::config::SiteConfig::current().page_size;
+    let mut url = api_url(&["posts", "tag", &tag, "meta", "page", &page.to_string()])?;
+    url.query_pairs_mut()
+        .append_pair("per_page", &per_page.to_string());
+    let meta = get_json::<aftershock_bridge::Paged<aftershock_bridge::PostMeta>>(url).await?;
     Ok(meta)
 }
+
+/// The full post behind a password, [`AppError::Forbidden`] when the password is wrong.
+#[server]
+pub async fn unlock_post(
+    uid: String,
+    password: String,
+) -> Result<aftershock_bridge::Post, AppError> {
+    let url = api_url(&["posts", "uid", &uid, "unlock"])?;
+    let response = reqwest::Client::new()
+        .post(url)
+        .json(&aftershock_bridge::UnlockPost { password })
+        .send()
+        .await?;
+    json::<aftershock_bridge::Post>(response).await
+}
+
+#[server]
+pub async fn get_preview(token: String) -> Result<aftershock_bridge::Post, AppError> {
+    let url = api_url(&["preview", &token])?;
+    let post = get_json::<aftershock_bridge::Post>(url).await?;
+    Ok(post)
+}
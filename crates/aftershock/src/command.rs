@@ -0,0 +1,24 @@
+use clap::{Parser, Subcommand};
+
+#[derive(Parser)]
+#[command(version, about)]
+#[command(propagate_version = true)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Commands>,
+}
+
+#[derive(Subcommand)]
+pub enum Commands {
+    /// Serve the site, the default when no command is given
+    Serve,
+    /// Render every route of the site into a directory any static file server can host
+    Generate {
+        /// The directory to write the site into
+        #[arg(long, default_value = "./dist")]
+        out: String,
+        /// Leave out the hydration scripts, the pages are plain HTML and CSS
+        #[arg(long)]
+        no_hydrate: bool,
+    },
+}
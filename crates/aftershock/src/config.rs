@@ -0,0 +1,235 @@
+use std::sync::LazyLock;
+
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::datetime::{provide_time_settings, TimeSettings};
+
+static CONFIG: LazyLock<SiteConfig> = LazyLock::new(SiteConfig::load);
+
+/// Everything that makes a site its own. Loaded once on the server from the TOML file in
+/// `AFTERSHOCK_SITE_CONFIG` (`./site.toml` by default), every missing field keeps the
+/// default below.
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct SiteConfig {
+    pub title: String,
+    pub description: String,
+    pub author: String,
+    /// BCP 47 tag used for `<html lang>`
+    pub language: String,
+    /// Public URL of the site without the trailing slash
+    pub base_url: String,
+    /// Where the server reaches the storage API, never sent to the browser
+    #[serde(skip_serializing)]
+    pub api_base: String,
+    /// Links in front of the pages that have a `nav_order`
+    pub nav: Vec<NavLink>,
+    /// Posts per page on the home and tag pages
+    pub page_size: i64,
+    pub footer: Option<String>,
+    pub license: License,
+    pub messages: Messages,
+    pub time: TimeSettings,
+    /// Social preview images, server side only
+    #[serde(skip_serializing)]
+    pub og_image: OgImage,
+    /// What `/robots.txt` asks of crawlers, server side only
+    #[serde(skip_serializing)]
+    pub robots: Robots,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct OgImage {
+    /// Where rendered cards are kept
+    pub cache_dir: String,
+    /// Fonts for the cards, put a CJK font here for CJK titles
+    pub fonts_dir: String,
+}
+
+impl Default for OgImage {
+    fn default() -> Self {
+        Self {
+            cache_dir: "./cache/og".into(),
+            fonts_dir: "./assets/fonts".into(),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct Robots {
+    /// Path prefixes crawlers are asked to stay out of
+    pub disallow: Vec<String>,
+    /// Lines appended as they are, for other user agents or rules
+    pub extra: Option<String>,
+}
+
+impl Default for Robots {
+    fn default() -> Self {
+        Self {
+            disallow: vec!["/preview/".into()],
+            extra: None,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct NavLink {
+    pub title: String,
+    pub href: String,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct License {
+    pub name: String,
+    pub url: String,
+    /// Badges shown next to every post, in order
+    pub icons: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct Messages {
+    pub load_data_failure: String,
+    pub data_not_found: String,
+    pub storage_unavailable: String,
+    pub bad_request: String,
+    pub archive_placeholder: String,
+    pub protected: String,
+    pub wrong_password: String,
+    pub draft_preview: String,
+    pub search_placeholder: String,
+    pub search_no_results: String,
+    pub tag_index: String,
+    pub archive: String,
+    pub prev_post: String,
+    pub next_post: String,
+    pub related_posts: String,
+    pub toc: String,
+    pub sort_by_name: String,
+    pub sort_by_count: String,
+    pub tag_list: String,
+    pub tag_cloud: String,
+    pub theme: String,
+    pub theme_light: String,
+    pub theme_dark: String,
+    pub theme_system: String,
+    pub copy_code: String,
+    pub code_copied: String,
+}
+
+impl Default for SiteConfig {
+    fn default() -> Self {
+        Self {
+            title: "破碎镜隙映影".into(),
+            description: String::new(),
+            author: "Aspirin".into(),
+            language: "zh-CN".into(),
+            base_url: "http://127.0.0.1:3000".into(),
+            api_base: "http://127.0.0.1:3030/api/v1".into(),
+            nav: vec![NavLink {
+                title: "主页".into(),
+                href: "/".into(),
+            }],
+            page_size: 10,
+            footer: None,
+            license: License::default(),
+            messages: Messages::default(),
+            time: TimeSettings::default(),
+            og_image: OgImage::default(),
+            robots: Robots::default(),
+        }
+    }
+}
+
+impl Default for License {
+    fn default() -> Self {
+        let icon = |name: &str| {
+            format!("https://mirrors.creativecommons.org/presskit/icons/{name}.svg?ref=chooser-v1")
+        };
+
+        Self {
+            name: "CC BY-NC-SA".into(),
+            url: "https://creativecommons.org/licenses/by-nc-sa/4.0/".into(),
+            icons: ["cc", "by", "nc", "sa"].into_iter().map(icon).collect(),
+        }
+    }
+}
+
+impl Default for Messages {
+    fn default() -> Self {
+        Self {
+            load_data_failure: "无法从破碎镜隙映影中取回你想要的讯息。".into(),
+            data_not_found: "破碎镜隙映影中无法找到你想要的讯息。".into(),
+            storage_unavailable: "破碎镜隙映影暂时沉寂，请稍后再来。".into(),
+            bad_request: "破碎镜隙映影无法理解你想要的讯息。".into(),
+            archive_placeholder: "正在从破碎镜隙映影中整理你想要的讯息。".into(),
+            protected: "这段讯息被封存在破碎镜隙映影深处，需要口令才能取回。".into(),
+            wrong_password: "口令无法映出你想要的讯息。".into(),
+            draft_preview: "草稿预览：这段讯息尚未在破碎镜隙映影中公开。".into(),
+            search_placeholder: "搜索".into(),
+            search_no_results: "破碎镜隙映影中没有映出与之相符的讯息。".into(),
+            tag_index: "标签".into(),
+            archive: "归档".into(),
+            prev_post: "上一篇".into(),
+            next_post: "下一篇".into(),
+            related_posts: "相关映影".into(),
+            toc: "目录".into(),
+            sort_by_name: "按名称".into(),
+            sort_by_count: "按篇数".into(),
+            tag_list: "列表".into(),
+            tag_cloud: "标签云".into(),
+            theme: "配色".into(),
+            theme_light: "浅色".into(),
+            theme_dark: "深色".into(),
+            theme_system: "跟随系统".into(),
+            copy_code: "复制".into(),
+            code_copied: "已复制".into(),
+        }
+    }
+}
+
+impl SiteConfig {
+    /// The configuration of this server, loaded on first use.
+    pub fn current() -> &'static SiteConfig {
+        &CONFIG
+    }
+
+    #[cfg(feature = "ssr")]
+    fn load() -> Self {
+        let path = std::env::var("AFTERSHOCK_SITE_CONFIG").unwrap_or_else(|_| "./site.toml".into());
+        let config = match std::fs::read_to_string(&path) {
+            Ok(text) => toml::from_str::<SiteConfig>(&text)
+                .unwrap_or_else(|e| panic!("Invalid site config {path}: {e}")),
+            Err(_) => SiteConfig::default(),
+        };
+
+        SiteConfig {
+            api_base: std::env::var("AFTERSHOCK_API_BASE")
+                .unwrap_or(config.api_base)
+                .trim_end_matches('/')
+                .into(),
+            time: config.time.with_env(),
+            ..config
+        }
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    fn load() -> Self {
+        SiteConfig::default()
+    }
+}
+
+/// Share the [`SiteConfig`] with the browser and provide it as context.
+pub fn provide_site_config() {
+    let config = SharedValue::new(|| SiteConfig::current().clone()).into_inner();
+    provide_time_settings(config.time.clone());
+    provide_context(config);
+}
+
+pub fn use_site_config() -> SiteConfig {
+    use_context::<SiteConfig>().unwrap_or_else(|| SiteConfig::current().clone())
+}
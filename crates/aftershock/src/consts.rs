@@ -1,4 +0,0 @@
-pub static TITLE: &str = "破碎镜隙映影";
-pub static MSG_LOAD_DATA_FAILURE: &str = "无法从破碎镜隙映影中取回你想要的讯息。";
-pub static MSG_DATA_NOT_FOUND: &str = "破碎镜隙映影中无法找到你想要的讯息。";
-pub static MSG_ARCHIVE_PLACEHOLDER: &str = "正在从破碎镜隙映影中整理你想要的讯息。";
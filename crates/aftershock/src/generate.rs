@@ -0,0 +1,261 @@
+//! Static export of the whole site, `aftershock generate`.
+//!
+//! The routes come from storage, the same way the sitemap finds them, and every one is
+//! rendered by the same components as the server, fully resolved, through an in-process
+//! router. Files land at `<route>/index.html`, next to the compiled assets of the site
+//! root, so any static file server can host the directory.
+
+use std::path::{Path, PathBuf};
+
+use axum::{body::Body, http::Request, routing::get, Router};
+use leptos::prelude::LeptosOptions;
+use reqwest::Url;
+use serde::Serialize;
+use thiserror::Error;
+use tower::ServiceExt;
+
+use crate::{app::static_shell, config::SiteConfig, og, sitemap};
+
+/// Where a static server looks for the page of a missing path
+const NOT_FOUND_FILE: &str = "404.html";
+
+#[derive(Error, Debug)]
+pub enum GenerateError {
+    #[error("Failed to fetch the routes: {0}")]
+    Fetch(#[from] reqwest::Error),
+    #[error("The API base {0} is not a base URL")]
+    ApiBase(String),
+    #[error("No site root at {0}, build the site first")]
+    SiteRoot(String),
+    #[error("IO Error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct GenerateReport {
+    pub written: usize,
+    /// Routes that did not render, with their status
+    pub failed: Vec<(String, u16)>,
+}
+
+/// Render every route into `out`, then copy the site root beside them. Without `hydrate`
+/// the pages carry no scripts and the WebAssembly bundle is left out.
+pub async fn generate(
+    options: LeptosOptions,
+    out: &Path,
+    hydrate: bool,
+) -> Result<GenerateReport, GenerateError> {
+    // Serving sets this up along with the routes, rendering alone does not
+    let _ = any_spawner::Executor::init_tokio();
+
+    let app = Router::new()
+        .route("/og/{file}", get(og::og_image))
+        .route("/sitemap.xml", get(sitemap::sitemap))
+        .route("/sitemaps/{file}", get(sitemap::sitemap_part))
+        .route("/robots.txt", get(sitemap::robots))
+        .fallback(leptos_axum::render_app_async({
+            let options = options.clone();
+            move || static_shell(options.clone(), hydrate)
+        }));
+
+    let site_root = Path::new(options.site_root.as_ref());
+    if !site_root.is_dir() {
+        return Err(GenerateError::SiteRoot(site_root.display().to_string()));
+    }
+    tokio::fs::create_dir_all(out).await?;
+    copy_site_root(site_root, out, hydrate)?;
+
+    let mut report = GenerateReport::default();
+    for route in routes().await? {
+        let (status, mut body) = render(&app, &route).await;
+        if status != 200 {
+            report
+                .failed
+                .push((format!("/{}", route.join("/")), status));
+            continue;
+        }
+        if !hydrate && file_of(&route).ends_with("index.html") {
+            body = without_scripts(&body);
+        }
+        write(&out.join(file_of(&route)), &body).await?;
+        report.written += 1;
+
+        // A sitemap index points at its parts, which are routes of their own
+        if route == ["sitemap.xml"] && body.windows(13).any(|x| x == b"<sitemapindex") {
+            for n in 1.. {
+                let part = vec!["sitemaps".to_string(), format!("{n}.xml")];
+                let (status, body) = render(&app, &part).await;
+                if status != 200 {
+                    break;
+                }
+                write(&out.join(file_of(&part)), &body).await?;
+                report.written += 1;
+            }
+        }
+    }
+
+    // Any path no route claims renders the not found page
+    let (_, mut body) = render(&app, &["__not_found__".to_string()]).await;
+    if !hydrate {
+        body = without_scripts(&body);
+    }
+    write(&out.join(NOT_FOUND_FILE), &body).await?;
+    report.written += 1;
+
+    Ok(report)
+}
+
+/// Every route of the site, as path segments.
+async fn routes() -> Result<Vec<Vec<String>>, GenerateError> {
+    let config = SiteConfig::current();
+    let posts = fetch::<Vec<aftershock_bridge::PostMeta>>(&["posts", "meta"], &[]).await?;
+    let pages = fetch::<Vec<aftershock_bridge::PostMeta>>(&["pages", "meta"], &[]).await?;
+    let tags = fetch::<Vec<aftershock_bridge::TagCount>>(&["posts", "tags"], &[]).await?;
+    let tz = config.time.timezone.name();
+    let months =
+        fetch::<Vec<aftershock_bridge::ArchiveMonth>>(&["posts", "archive"], &[("tz", tz)]).await?;
+
+    let route = |segments: &[&str]| segments.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+    let page_count = |count: usize| (count as i64 + config.page_size - 1) / config.page_size;
+    let mut routes = vec![route(&[])];
+    for n in 2..=page_count(posts.len()) {
+        routes.push(route(&["page", &n.to_string()]));
+    }
+
+    // Protected posts unlock through a server function, which a static site does not have
+    for post in posts.iter().filter(|x| x.visibility != "protected") {
+        routes.push(route(&["posts", &post.uid]));
+        routes.push(route(&["og", &format!("{}.png", post.uid)]));
+    }
+    for page in pages.iter().filter(|x| x.visibility == "public") {
+        routes.push(route(&[&page.uid]));
+    }
+
+    routes.push(route(&["tags"]));
+    for tag in &tags {
+        routes.push(route(&["tags", &tag.tag]));
+        for n in 2..=page_count(tag.count as usize) {
+            routes.push(route(&["tags", &tag.tag, "page", &n.to_string()]));
+        }
+    }
+
+    routes.push(route(&["archive"]));
+    let mut years = months.iter().map(|x| x.year).collect::<Vec<_>>();
+    years.sort_unstable();
+    years.dedup();
+    for year in years {
+        routes.push(route(&["archive", &year.to_string()]));
+    }
+    for month in &months {
+        let (year, month) = (month.year.to_string(), month.month.to_string());
+        routes.push(route(&["archive", &year, &month]));
+    }
+
+    routes.push(route(&["sitemap.xml"]));
+    routes.push(route(&["robots.txt"]));
+    Ok(routes)
+}
+
+/// GET the storage API at `segments` with `query`, both percent-encoded.
+async fn fetch<T: serde::de::DeserializeOwned>(
+    segments: &[&str],
+    query: &[(&str, &str)],
+) -> Result<T, GenerateError> {
+    let base = &SiteConfig::current().api_base;
+    let mut url = Url::parse(base).map_err(|_| GenerateError::ApiBase(base.clone()))?;
+    url.path_segments_mut()
+        .map_err(|_| GenerateError::ApiBase(base.clone()))?
+        .pop_if_empty()
+        .extend(segments);
+    if !query.is_empty() {
+        url.query_pairs_mut().extend_pairs(query);
+    }
+    Ok(reqwest::get(url)
+        .await?
+        .error_for_status()?
+        .json::<T>()
+        .await?)
+}
+
+/// Ask `app` for `route`, answering with the status and body.
+async fn render(app: &Router, route: &[String]) -> (u16, Vec<u8>) {
+    let mut url = Url::parse("http://localhost/").unwrap();
+    url.path_segments_mut()
+        .unwrap()
+        .pop_if_empty()
+        .extend(route);
+    let request = Request::get(url.path()).body(Body::empty()).unwrap();
+
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status().as_u16();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .map(|x| x.to_vec())
+        .unwrap_or_default();
+    (status, body)
+}
+
+/// The file a static server answers `route` with: files keep their name, pages become
+/// the index of their directory.
+fn file_of(route: &[String]) -> PathBuf {
+    let mut file = route.iter().collect::<PathBuf>();
+    if !matches!(
+        file.extension().and_then(|x| x.to_str()),
+        Some("xml" | "txt" | "png")
+    ) {
+        file.push("index.html");
+    }
+    file
+}
+
+/// `html` without its scripts, the resource data Leptos leaves for hydration included.
+/// JSON-LD is data for crawlers and stays.
+fn without_scripts(html: &[u8]) -> Vec<u8> {
+    let html = String::from_utf8_lossy(html);
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html.as_ref();
+    while let Some(start) = rest.find("<script") {
+        let (before, script) = rest.split_at(start);
+        out.push_str(before);
+        let Some(end) = script.find("</script>").map(|x| x + "</script>".len()) else {
+            rest = script;
+            break;
+        };
+        let tag = &script[..script.find('>').unwrap_or(end)];
+        if tag.contains("application/ld+json") {
+            out.push_str(&script[..end]);
+        }
+        rest = &script[end..];
+    }
+    out.push_str(rest);
+    out.into_bytes()
+}
+
+async fn write(path: &Path, body: &[u8]) -> Result<(), GenerateError> {
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await?;
+    }
+    tokio::fs::write(path, body).await?;
+    Ok(())
+}
+
+/// Copy the compiled assets, without the scripts and WebAssembly when not hydrating.
+fn copy_site_root(from: &Path, to: &Path, hydrate: bool) -> Result<(), GenerateError> {
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let path = entry.path();
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            std::fs::create_dir_all(&target)?;
+            copy_site_root(&path, &target, hydrate)?;
+        } else if hydrate
+            || !matches!(
+                path.extension().and_then(|x| x.to_str()),
+                Some("js" | "wasm")
+            )
+        {
+            std::fs::copy(&path, &target)?;
+        }
+    }
+    Ok(())
+}
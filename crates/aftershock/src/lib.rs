@@ -1,15 +1,55 @@
 #![recursion_limit = "256"]
 
 pub mod app;
-mod consts;
+#[cfg(feature = "ssr")]
+pub mod command;
+pub mod config;
+#[cfg(feature = "ssr")]
+pub mod generate;
+#[cfg(feature = "ssr")]
+pub mod og;
+#[cfg(feature = "ssr")]
+pub mod sitemap;
 mod utils;
 
-pub use consts::*;
-
 #[cfg(feature = "hydrate")]
 #[wasm_bindgen::prelude::wasm_bindgen]
 pub fn hydrate() {
     use crate::app::*;
     console_error_panic_hook::set_once();
+    static_export_links();
     leptos::mount::hydrate_lazy(App);
 }
+
+/// A static export has no server functions to load the next page with, so its links are
+/// left to the browser, which loads the pre-rendered page instead.
+#[cfg(feature = "hydrate")]
+fn static_export_links() {
+    use wasm_bindgen::{closure::Closure, JsCast};
+
+    let window = leptos::prelude::window();
+    let is_static = window
+        .document()
+        .and_then(|document| document.body())
+        .is_some_and(|body| body.has_attribute("data-static-export"));
+    if !is_static {
+        return;
+    }
+
+    // Caught before the router sees it, which would navigate in place
+    let on_click = Closure::<dyn Fn(web_sys::MouseEvent)>::new(|ev: web_sys::MouseEvent| {
+        let link = ev
+            .target()
+            .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+            .and_then(|element| element.closest("a[href]").ok().flatten());
+        if link.is_some() {
+            ev.stop_propagation();
+        }
+    });
+    let _ = window.add_event_listener_with_callback_and_bool(
+        "click",
+        on_click.as_ref().unchecked_ref(),
+        true,
+    );
+    on_click.forget();
+}
@@ -1,19 +1,50 @@
 #[cfg(feature = "ssr")]
 #[tokio::main]
 async fn main() {
+    use aftershock::command::{Cli, Commands};
+    use aftershock::config::SiteConfig;
+    use clap::Parser;
+    use leptos::prelude::*;
+
+    let cli = Cli::parse();
+
+    // Load the site config up front so a broken file stops right away
+    let _ = SiteConfig::current();
+
+    let conf = get_configuration(None).unwrap();
+
+    match cli.command.unwrap_or(Commands::Serve) {
+        Commands::Serve => serve(conf.leptos_options).await,
+        Commands::Generate { out, no_hydrate } => {
+            let report =
+                aftershock::generate::generate(conf.leptos_options, out.as_ref(), !no_hydrate)
+                    .await
+                    .expect("Fail to generate");
+            println!("{}", serde_json::to_string_pretty(&report).unwrap());
+            if !report.failed.is_empty() {
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "ssr")]
+async fn serve(leptos_options: leptos::prelude::LeptosOptions) {
     use aftershock::app::*;
-    use axum::Router;
+    use aftershock::sitemap;
+    use axum::{routing::get, Router};
     use leptos::logging::log;
-    use leptos::prelude::*;
     use leptos_axum::{generate_route_list, LeptosRoutes};
 
-    let conf = get_configuration(None).unwrap();
-    let addr = conf.leptos_options.site_addr;
-    let leptos_options = conf.leptos_options;
+    let addr = leptos_options.site_addr;
     // Generate the list of routes in your Leptos App
     let routes = generate_route_list(App);
 
     let app = Router::new()
+        .route("/og/{file}", get(aftershock::og::og_image))
+        .route("/sitemap.xml", get(sitemap::sitemap))
+        .route("/sitemaps/{file}", get(sitemap::sitemap_part))
+        .route("/robots.txt", get(sitemap::robots))
         .leptos_routes(&leptos_options, routes, {
             let leptos_options = leptos_options.clone();
             move || shell(leptos_options.clone())
@@ -0,0 +1,307 @@
+//! Social preview images served at `/og/:uid.png`.
+//!
+//! A card is an SVG template filled with the post, rasterized with resvg. Fonts come from
+//! the directory in [`OgImage::fonts_dir`](crate::config::OgImage), then the system, and
+//! glyphs missing from the first family fall back to any loaded font, so a CJK font there
+//! is enough for CJK titles. Rendered cards are cached on disk as `<uid>-<updated_at>.png`,
+//! an edit changes the name and the stale file is removed on the next render.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, LazyLock},
+};
+
+use axum::{
+    http::{
+        header::{CACHE_CONTROL, CONTENT_TYPE},
+        StatusCode,
+    },
+    response::{IntoResponse, Response},
+};
+use resvg::{tiny_skia, usvg};
+use thiserror::Error;
+
+use crate::{config::SiteConfig, utils::datetime::AppDateTime};
+
+const WIDTH: u32 = 1200;
+const HEIGHT: u32 = 630;
+const MARGIN: f32 = 80.0;
+const TITLE_SIZE: f32 = 64.0;
+const TITLE_LINES: usize = 3;
+const FOOTER_SIZE: f32 = 30.0;
+const FONT_FAMILY: &str =
+    "'Noto Sans SC', 'Noto Sans CJK SC', 'Source Han Sans SC', 'Noto Sans', sans-serif";
+
+static FONTS: LazyLock<Arc<usvg::fontdb::Database>> = LazyLock::new(|| {
+    let mut fonts = usvg::fontdb::Database::new();
+    let dir = &SiteConfig::current().og_image.fonts_dir;
+    fonts.load_fonts_dir(dir);
+    let bundled = first_family(&fonts, |_| true);
+    if bundled.is_none() {
+        leptos::logging::warn!(
+            "No fonts in {}, preview cards fall back to system fonts and CJK titles may render \
+             as boxes, see assets/fonts/README.md",
+            dir
+        );
+    }
+    fonts.load_system_fonts();
+    // fontdb takes `sans-serif` for Arial, which few servers have, so it names a font
+    // that is actually there: a bundled one, a usual sans, or anything at all
+    let family = bundled
+        .or_else(|| first_family(&fonts, |x| SANS_SERIF.contains(&x)))
+        .or_else(|| first_family(&fonts, |_| true));
+    if let Some(family) = family {
+        fonts.set_sans_serif_family(family);
+    }
+    Arc::new(fonts)
+});
+const SANS_SERIF: [&str; 4] = ["Noto Sans", "DejaVu Sans", "Liberation Sans", "Arial"];
+
+fn first_family(fonts: &usvg::fontdb::Database, accept: impl Fn(&str) -> bool) -> Option<String> {
+    fonts
+        .faces()
+        .flat_map(|face| face.families.iter().map(|(family, _)| family))
+        .find(|family| accept(family))
+        .cloned()
+}
+
+#[derive(Error, Debug)]
+pub enum OgError {
+    #[error("No such post")]
+    NotFound,
+    #[error("The API base {0} is not a base URL")]
+    ApiBase(String),
+    #[error("Failed to fetch the post: {0}")]
+    Fetch(#[from] reqwest::Error),
+    #[error("Failed to render the card: {0}")]
+    Render(String),
+    #[error("IO Error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl IntoResponse for OgError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            OgError::NotFound => StatusCode::NOT_FOUND,
+            OgError::Fetch(_) => StatusCode::BAD_GATEWAY,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, self.to_string()).into_response()
+    }
+}
+
+pub async fn og_image(axum::extract::Path(file): axum::extract::Path<String>) -> Response {
+    // Axum has decoded the path, a `/` here would reach another storage endpoint
+    let Some(uid) = file.strip_suffix(".png").filter(|uid| !uid.contains('/')) else {
+        return OgError::NotFound.into_response();
+    };
+
+    match card(uid).await {
+        Ok(png) => (
+            [
+                (CONTENT_TYPE, "image/png"),
+                (CACHE_CONTROL, "public, max-age=3600"),
+            ],
+            png,
+        )
+            .into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn card(uid: &str) -> Result<Vec<u8>, OgError> {
+    let config = SiteConfig::current();
+    let mut url = reqwest::Url::parse(&config.api_base)
+        .map_err(|_| OgError::ApiBase(config.api_base.clone()))?;
+    url.path_segments_mut()
+        .map_err(|_| OgError::ApiBase(config.api_base.clone()))?
+        .pop_if_empty()
+        .extend(["posts", "uid", uid]);
+    let response = reqwest::get(url).await?;
+    if response.status() == StatusCode::NOT_FOUND {
+        return Err(OgError::NotFound);
+    }
+    let post = response
+        .error_for_status()?
+        .json::<aftershock_bridge::Post>()
+        .await?;
+
+    let cache_dir = PathBuf::from(&config.og_image.cache_dir);
+    let path = cache_dir.join(format!("{}-{}.png", post.uid, post.updated_at));
+    if let Ok(png) = tokio::fs::read(&path).await {
+        return Ok(png);
+    }
+
+    let png = tokio::task::spawn_blocking(move || render(&post))
+        .await
+        .map_err(|e| OgError::Render(e.to_string()))??;
+
+    tokio::fs::create_dir_all(&cache_dir).await?;
+    remove_stale(&cache_dir, uid, &path).await;
+    // Written aside then renamed, so a concurrent request never reads half a file
+    let partial = path.with_extension("png.partial");
+    tokio::fs::write(&partial, &png).await?;
+    tokio::fs::rename(&partial, &path).await?;
+
+    Ok(png)
+}
+
+/// Remove the cards rendered for older versions of `uid`.
+async fn remove_stale(cache_dir: &Path, uid: &str, current: &Path) {
+    let Ok(mut entries) = tokio::fs::read_dir(cache_dir).await else {
+        return;
+    };
+    let prefix = format!("{uid}-");
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let name = entry.file_name();
+        let stale = name.to_str().is_some_and(|name| {
+            name.strip_prefix(&prefix)
+                .and_then(|rest| rest.strip_suffix(".png"))
+                .is_some_and(|updated_at| updated_at.parse::<i64>().is_ok())
+        });
+        if stale && entry.path() != current {
+            let _ = tokio::fs::remove_file(entry.path()).await;
+        }
+    }
+}
+
+fn render(post: &aftershock_bridge::Post) -> Result<Vec<u8>, OgError> {
+    let options = usvg::Options {
+        fontdb: FONTS.clone(),
+        font_family: "sans-serif".into(),
+        ..Default::default()
+    };
+    let tree =
+        usvg::Tree::from_str(&svg(post), &options).map_err(|e| OgError::Render(e.to_string()))?;
+
+    let mut pixmap = tiny_skia::Pixmap::new(WIDTH, HEIGHT)
+        .ok_or_else(|| OgError::Render("Empty canvas".into()))?;
+    resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+    pixmap
+        .encode_png()
+        .map_err(|e| OgError::Render(e.to_string()))
+}
+
+fn svg(post: &aftershock_bridge::Post) -> String {
+    let config = SiteConfig::current();
+    let date =
+        AppDateTime::from_timestamp(post.created_at, config.time.timezone, config.time.locale)
+            .human_readable();
+    let line_width = WIDTH as f32 - 2.0 * MARGIN;
+
+    // As many tags as fit on their line, the rest is left out
+    let mut tags = String::new();
+    for tag in &post.tags {
+        let tag = format!("#{tag}  ");
+        if text_width(&tags) + text_width(tag.trim_end()) > line_width / FOOTER_SIZE {
+            break;
+        }
+        tags.push_str(&tag);
+    }
+
+    let title = wrap(&post.title, line_width / TITLE_SIZE, TITLE_LINES)
+        .into_iter()
+        .enumerate()
+        .map(|(index, line)| {
+            format!(
+                r#"<tspan x="{MARGIN}" dy="{}">{}</tspan>"#,
+                if index == 0 { 0.0 } else { TITLE_SIZE * 1.25 },
+                escape(&line)
+            )
+        })
+        .collect::<String>();
+
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{HEIGHT}" viewBox="0 0 {WIDTH} {HEIGHT}">
+<rect width="100%" height="100%" fill="#f6f3f0"/>
+<rect x="{MARGIN}" y="150" width="{line_width}" height="4" fill="#1c1c1c"/>
+<g font-family="{FONT_FAMILY}" fill="#1c1c1c">
+<text x="{MARGIN}" y="120" font-size="36" font-weight="bold">{site}</text>
+<text x="{MARGIN}" y="260" font-size="{TITLE_SIZE}" font-weight="bold">{title}</text>
+<text x="{MARGIN}" y="{tags_y}" font-size="{FOOTER_SIZE}">{tags}</text>
+<text x="{MARGIN}" y="{date_y}" font-size="{FOOTER_SIZE}" fill="#5c5c5c">{date}</text>
+</g>
+</svg>"##,
+        site = escape(&config.title),
+        tags_y = HEIGHT as f32 - MARGIN - FOOTER_SIZE * 1.6,
+        date_y = HEIGHT as f32 - MARGIN,
+        date = escape(&date),
+        tags = escape(tags.trim_end()),
+    )
+}
+
+/// Width of `c` in ems, roughly: full width for CJK and other wide scripts.
+fn char_width(c: char) -> f32 {
+    match c as u32 {
+        0x1100..=0x115F | 0x2E80..=0xA4CF | 0xAC00..=0xD7A3 | 0xF900..=0xFAFF | 0xFF00..=0xFF60 => {
+            1.0
+        }
+        _ if c.is_uppercase() => 0.75,
+        _ => 0.64,
+    }
+}
+
+fn text_width(text: &str) -> f32 {
+    text.chars().map(char_width).sum()
+}
+
+/// Break `text` into at most `max_lines` lines of `max_width` ems, words kept whole
+/// unless they are too long for a line, an ellipsis marks what did not fit.
+fn wrap(text: &str, max_width: f32, max_lines: usize) -> Vec<String> {
+    // Latin words stay together, every wide character is a word of its own
+    let mut words: Vec<String> = vec![];
+    let mut word = String::new();
+    for c in text.chars() {
+        if c.is_whitespace() || char_width(c) >= 1.0 {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            words.push(c.to_string());
+        } else {
+            word.push(c);
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+
+    let mut lines: Vec<String> = vec![String::new()];
+    for word in words {
+        let line = lines.last_mut().unwrap();
+        if text_width(line) + text_width(&word) <= max_width {
+            line.push_str(&word);
+        } else if word.trim().is_empty() {
+            lines.push(String::new());
+        } else if text_width(&word) <= max_width {
+            lines.push(word.trim_start().to_owned());
+        } else {
+            // A word longer than a line is split wherever it overflows
+            for c in word.chars() {
+                let line = lines.last_mut().unwrap();
+                if text_width(line) + char_width(c) > max_width {
+                    lines.push(String::new());
+                }
+                lines.last_mut().unwrap().push(c);
+            }
+        }
+    }
+
+    let mut lines: Vec<String> = lines.into_iter().map(|x| x.trim().to_owned()).collect();
+    lines.retain(|x| !x.is_empty());
+    if lines.len() > max_lines {
+        lines.truncate(max_lines);
+        let last = lines.last_mut().unwrap();
+        while !last.is_empty() && text_width(last) + 1.0 > max_width {
+            last.pop();
+        }
+        last.push('…');
+    }
+    lines
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
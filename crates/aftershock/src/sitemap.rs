@@ -0,0 +1,228 @@
+//! `/sitemap.xml` and `/robots.txt`.
+//!
+//! The sitemap lists the home page, every listed post, every public page, the tag and
+//! year archives, each with the newest `updated_at` behind it. Past the protocol limits
+//! of 50,000 URLs or 50 MiB it turns into a sitemap index of `/sitemaps/<n>.xml`.
+
+use std::collections::BTreeMap;
+
+use axum::{
+    extract::Path,
+    http::{header::CONTENT_TYPE, StatusCode},
+    response::{IntoResponse, Response},
+};
+use chrono::{TimeZone, Utc};
+use reqwest::Url;
+use thiserror::Error;
+
+use crate::{config::SiteConfig, utils::datetime::AppDateTime};
+
+const MAX_URLS: usize = 50_000;
+const MAX_BYTES: usize = 50 * 1024 * 1024;
+const URLSET_OPEN: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+"#;
+const URLSET_CLOSE: &str = "</urlset>\n";
+
+#[derive(Error, Debug)]
+pub enum SitemapError {
+    #[error("No such sitemap")]
+    NotFound,
+    #[error("Failed to fetch the posts: {0}")]
+    Fetch(#[from] reqwest::Error),
+    #[error("Invalid base_url: {0}")]
+    BaseUrl(String),
+}
+
+impl IntoResponse for SitemapError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            SitemapError::NotFound => StatusCode::NOT_FOUND,
+            SitemapError::Fetch(_) => StatusCode::BAD_GATEWAY,
+            SitemapError::BaseUrl(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, self.to_string()).into_response()
+    }
+}
+
+/// One `<url>` of the sitemap, already serialized.
+struct Entry {
+    xml: String,
+    lastmod: Option<i64>,
+}
+
+pub async fn sitemap() -> Result<Response, SitemapError> {
+    let parts = parts().await?;
+    if let [part] = parts.as_slice() {
+        return Ok(xml(urlset(part)));
+    }
+
+    let base = base_url()?;
+    let mut index = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+"#,
+    );
+    for (n, part) in parts.iter().enumerate() {
+        let loc = locate(&base, &["sitemaps", &format!("{}.xml", n + 1)])?;
+        let lastmod = part.iter().filter_map(|entry| entry.lastmod).max();
+        index.push_str(&element("sitemap", &loc, lastmod));
+    }
+    index.push_str("</sitemapindex>\n");
+    Ok(xml(index))
+}
+
+/// The `n`th sitemap of the index, `file` being `<n>.xml` from 1.
+pub async fn sitemap_part(Path(file): Path<String>) -> Result<Response, SitemapError> {
+    let n = file
+        .strip_suffix(".xml")
+        .and_then(|n| n.parse::<usize>().ok())
+        .ok_or(SitemapError::NotFound)?;
+    let parts = parts().await?;
+    let part = n
+        .checked_sub(1)
+        .and_then(|n| parts.get(n))
+        .ok_or(SitemapError::NotFound)?;
+    Ok(xml(urlset(part)))
+}
+
+pub async fn robots() -> Response {
+    let config = SiteConfig::current();
+    let mut text = String::from("User-agent: *\n");
+    for path in &config.robots.disallow {
+        text.push_str(&format!("Disallow: {path}\n"));
+    }
+    if let Some(extra) = &config.robots.extra {
+        text.push('\n');
+        text.push_str(extra.trim_end());
+        text.push('\n');
+    }
+    text.push_str(&format!("\nSitemap: {}/sitemap.xml\n", config.base_url));
+    ([(CONTENT_TYPE, "text/plain; charset=utf-8")], text).into_response()
+}
+
+/// Every entry of the sitemap, cut into parts within the protocol limits.
+async fn parts() -> Result<Vec<Vec<Entry>>, SitemapError> {
+    let entries = entries().await?;
+    let mut parts: Vec<Vec<Entry>> = vec![vec![]];
+    let mut bytes = URLSET_OPEN.len() + URLSET_CLOSE.len();
+    for entry in entries {
+        let part = parts.last_mut().unwrap();
+        if !part.is_empty() && (part.len() == MAX_URLS || bytes + entry.xml.len() > MAX_BYTES) {
+            parts.push(vec![]);
+            bytes = URLSET_OPEN.len() + URLSET_CLOSE.len();
+        }
+        bytes += entry.xml.len();
+        parts.last_mut().unwrap().push(entry);
+    }
+    Ok(parts)
+}
+
+async fn entries() -> Result<Vec<Entry>, SitemapError> {
+    let config = SiteConfig::current();
+    let posts = reqwest::get(format!("{}/posts/meta", config.api_base))
+        .await?
+        .error_for_status()?
+        .json::<Vec<aftershock_bridge::PostMeta>>()
+        .await?;
+    let mut pages = reqwest::get(format!("{}/pages/meta", config.api_base))
+        .await?
+        .error_for_status()?
+        .json::<Vec<aftershock_bridge::PostMeta>>()
+        .await?;
+    pages.retain(|page| page.visibility == "public");
+
+    let newest = posts.iter().map(|post| post.updated_at).max();
+    let mut tags: BTreeMap<&str, i64> = BTreeMap::new();
+    let mut years: BTreeMap<i32, i64> = BTreeMap::new();
+    for post in &posts {
+        for tag in &post.tags {
+            let lastmod = tags.entry(tag).or_default();
+            *lastmod = (*lastmod).max(post.updated_at);
+        }
+        let year =
+            AppDateTime::from_timestamp(post.created_at, config.time.timezone, config.time.locale)
+                .year();
+        let lastmod = years.entry(year).or_default();
+        *lastmod = (*lastmod).max(post.updated_at);
+    }
+
+    let base = base_url()?;
+    let entry = |segments: &[&str], lastmod: Option<i64>| -> Result<Entry, SitemapError> {
+        let loc = locate(&base, segments)?;
+        Ok(Entry {
+            xml: element("url", &loc, lastmod),
+            lastmod,
+        })
+    };
+
+    let mut entries = vec![entry(&[], newest)?];
+    for post in &posts {
+        entries.push(entry(&["posts", &post.uid], Some(post.updated_at))?);
+    }
+    for page in &pages {
+        entries.push(entry(&[&page.uid], Some(page.updated_at))?);
+    }
+    if !tags.is_empty() {
+        entries.push(entry(&["tags"], newest)?);
+    }
+    for (tag, lastmod) in tags {
+        entries.push(entry(&["tags", tag], Some(lastmod))?);
+    }
+    if !years.is_empty() {
+        entries.push(entry(&["archive"], newest)?);
+    }
+    for (year, lastmod) in years.into_iter().rev() {
+        entries.push(entry(&["archive", &year.to_string()], Some(lastmod))?);
+    }
+    Ok(entries)
+}
+
+fn base_url() -> Result<Url, SitemapError> {
+    let base_url = &SiteConfig::current().base_url;
+    let base = Url::parse(&format!("{base_url}/"))
+        .map_err(|e| SitemapError::BaseUrl(format!("{base_url}: {e}")))?;
+    if base.cannot_be_a_base() {
+        return Err(SitemapError::BaseUrl(base_url.clone()));
+    }
+    Ok(base)
+}
+
+/// `base` with `segments` appended, each percent-encoded.
+fn locate(base: &Url, segments: &[&str]) -> Result<String, SitemapError> {
+    let mut url = base.clone();
+    url.path_segments_mut()
+        .map_err(|_| SitemapError::BaseUrl(base.to_string()))?
+        .pop_if_empty()
+        .extend(segments);
+    Ok(url.into())
+}
+
+fn element(name: &str, loc: &str, lastmod: Option<i64>) -> String {
+    let lastmod = lastmod
+        .and_then(|x| Utc.timestamp_opt(x, 0).single())
+        .map(|x| format!("<lastmod>{}</lastmod>", x.format("%Y-%m-%dT%H:%M:%SZ")))
+        .unwrap_or_default();
+    format!("<{name}><loc>{}</loc>{lastmod}</{name}>\n", escape(loc))
+}
+
+fn urlset(entries: &[Entry]) -> String {
+    let mut xml = String::from(URLSET_OPEN);
+    for entry in entries {
+        xml.push_str(&entry.xml);
+    }
+    xml.push_str(URLSET_CLOSE);
+    xml
+}
+
+fn xml(body: String) -> Response {
+    ([(CONTENT_TYPE, "application/xml; charset=utf-8")], body).into_response()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
@@ -1,214 +1,235 @@
-#[cfg(not(target_arch = "wasm32"))]
-use chrono::{Datelike, FixedOffset, TimeZone, Utc};
-
-pub trait DateTime {
-    fn from_timestamp(timestamp: i64) -> Self;
-
-    fn year(&self) -> i32;
-    fn month(&self) -> u32;
-    fn day(&self) -> u32;
-    fn orig(&self) -> i64;
-
-    fn human_readable(&self) -> &str;
-    fn machine_friendly(&self) -> &str;
-
-    fn month_to_abbr(&self) -> &'static str {
-        match self.month() {
-            1 => "Jan",
-            2 => "Feb",
-            3 => "Mar",
-            4 => "Apr",
-            5 => "May",
-            6 => "Jun",
-            7 => "Jul",
-            8 => "Aug",
-            9 => "Sep",
-            10 => "Oct",
-            11 => "Nov",
-            12 => "Dec",
-            _ => "",
-        }
-    }
-}
-
-// Type alias that selects implementation based on target architecture
-// Use UserLocalDateTime (js_sys) in wasm32 to avoid chrono dependency
-// Use PreformattedDateTime (chrono) in SSR/other targets
-#[cfg(target_arch = "wasm32")]
-pub type AppDateTime = UserLocalDateTime;
-
-#[cfg(not(target_arch = "wasm32"))]
-pub type AppDateTime = PreformattedDateTime;
-
-#[cfg(not(target_arch = "wasm32"))]
-#[derive(Clone, Debug)]
-pub struct PreformattedDateTime {
-    year: i32,
-    month: u32,
-    day: u32,
-    pub orig: i64,
-    human_readable: String,
-    machine_friendly: String,
+use chrono::{Datelike, TimeZone, Utc};
+use chrono_tz::Tz;
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Languages dates can be written in.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug, Default)]
+pub enum Locale {
+    #[default]
+    #[serde(rename = "zh-CN")]
+    ZhCn,
+    #[serde(rename = "en")]
+    En,
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-impl DateTime for PreformattedDateTime {
-    // FIXME: no unwrap here
-    fn from_timestamp(timestamp: i64) -> PreformattedDateTime {
-        let tz = FixedOffset::east_opt(8 * 3600).unwrap();
-        let utc_time = Utc.timestamp_opt(timestamp, 0).unwrap();
-        let tz_time = utc_time.with_timezone(&tz);
-        let human_readable = format!("{}", tz_time.format("%Y-%m-%d"));
-        let machine_friendly = tz_time.to_rfc3339();
-        let year = tz_time.year();
-        let month = tz_time.month();
-        let day = tz_time.day();
-        PreformattedDateTime {
-            year,
-            month,
-            day,
-            orig: timestamp,
-            human_readable,
-            machine_friendly,
+impl Locale {
+    pub fn month_name(&self, month: u32) -> &'static str {
+        const ZH_CN: [&str; 12] = [
+            "一月",
+            "二月",
+            "三月",
+            "四月",
+            "五月",
+            "六月",
+            "七月",
+            "八月",
+            "九月",
+            "十月",
+            "十一月",
+            "十二月",
+        ];
+        const EN: [&str; 12] = [
+            "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+        ];
+
+        let names = match self {
+            Locale::ZhCn => &ZH_CN,
+            Locale::En => &EN,
+        };
+        names
+            .get((month as usize).wrapping_sub(1))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn format_date(&self, year: i32, month: u32, day: u32) -> String {
+        match self {
+            Locale::ZhCn => format!("{year}年{month}月{day}日"),
+            Locale::En => format!("{} {day}, {year}", self.month_name(month)),
         }
     }
 
-    fn year(&self) -> i32 {
-        self.year
+    pub fn format_year_month(&self, year: i32, month: u32) -> String {
+        match self {
+            Locale::ZhCn => format!("{year}年{month}月"),
+            Locale::En => format!("{} {year}", self.month_name(month)),
+        }
     }
 
-    fn month(&self) -> u32 {
-        self.month
+    pub fn format_month_day(&self, month: u32, day: u32) -> String {
+        match self {
+            Locale::ZhCn => format!("{month}月{day}日"),
+            Locale::En => format!("{} {day}", self.month_name(month)),
+        }
     }
+}
 
-    fn day(&self) -> u32 {
-        self.day
-    }
+impl std::str::FromStr for Locale {
+    type Err = String;
 
-    fn orig(&self) -> i64 {
-        self.orig
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "zh-CN" | "zh" => Ok(Locale::ZhCn),
+            "en" | "en-US" | "en-GB" => Ok(Locale::En),
+            _ => Err(format!("Unsupported locale {s}")),
+        }
     }
+}
 
-    fn human_readable(&self) -> &str {
-        &self.human_readable
-    }
+/// How dates are shown across the site, part of the
+/// [`SiteConfig`](crate::config::SiteConfig) so hydration renders exactly what SSR did.
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct TimeSettings {
+    pub timezone: Tz,
+    pub locale: Locale,
+    /// Switch to the reader's own timezone once the page is hydrated
+    pub reader_local: bool,
+}
 
-    fn machine_friendly(&self) -> &str {
-        &self.machine_friendly
+impl Default for TimeSettings {
+    fn default() -> Self {
+        Self {
+            timezone: chrono_tz::Asia::Shanghai,
+            locale: Locale::default(),
+            reader_local: false,
+        }
     }
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-impl PartialEq for PreformattedDateTime {
-    fn eq(&self, other: &Self) -> bool {
-        self.orig() == other.orig()
+impl TimeSettings {
+    /// Override with `AFTERSHOCK_TIMEZONE`, `AFTERSHOCK_LOCALE` and
+    /// `AFTERSHOCK_READER_LOCAL_TIME` when they are set.
+    #[cfg(feature = "ssr")]
+    pub fn with_env(self) -> Self {
+        use std::env;
+
+        Self {
+            timezone: env::var("AFTERSHOCK_TIMEZONE")
+                .ok()
+                .and_then(|x| x.parse().ok())
+                .unwrap_or(self.timezone),
+            locale: env::var("AFTERSHOCK_LOCALE")
+                .ok()
+                .and_then(|x| x.parse().ok())
+                .unwrap_or(self.locale),
+            reader_local: env::var("AFTERSHOCK_READER_LOCAL_TIME")
+                .map(|x| matches!(x.as_str(), "1" | "true"))
+                .unwrap_or(self.reader_local),
+        }
     }
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-impl Eq for PreformattedDateTime {}
+/// The timezone dates are currently displayed in.
+#[derive(Clone, Copy)]
+pub struct DisplayTimeZone(pub ReadSignal<Tz>);
+
+/// Provide `settings`, together with the [`DisplayTimeZone`], as context.
+pub fn provide_time_settings(settings: TimeSettings) {
+    let (timezone, set_timezone) = signal(settings.timezone);
+    if settings.reader_local {
+        // Effects only run in the browser after hydration, SSR and the first client
+        // render both use the site timezone
+        Effect::new(move |_| {
+            if let Some(timezone) = reader_timezone() {
+                set_timezone.set(timezone);
+            }
+        });
+    }
+
+    provide_context(settings);
+    provide_context(DisplayTimeZone(timezone));
+}
 
-#[cfg(not(target_arch = "wasm32"))]
-impl PartialOrd for PreformattedDateTime {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
+pub fn use_time_settings() -> TimeSettings {
+    use_context::<TimeSettings>().unwrap_or_default()
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-impl Ord for PreformattedDateTime {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.orig().cmp(&other.orig())
+pub fn use_display_timezone() -> ReadSignal<Tz> {
+    match use_context::<DisplayTimeZone>() {
+        Some(DisplayTimeZone(timezone)) => timezone,
+        None => signal(TimeSettings::default().timezone).0,
     }
 }
 
-#[cfg(target_arch = "wasm32")]
+#[cfg(feature = "hydrate")]
+fn reader_timezone() -> Option<Tz> {
+    let format = js_sys::Intl::DateTimeFormat::new(&js_sys::Array::new(), &js_sys::Object::new());
+    js_sys::Reflect::get(&format.resolved_options(), &"timeZone".into())
+        .ok()?
+        .as_string()?
+        .parse()
+        .ok()
+}
+
+#[cfg(not(feature = "hydrate"))]
+fn reader_timezone() -> Option<Tz> {
+    None
+}
+
 #[derive(Clone, Debug)]
-pub struct UserLocalDateTime {
+pub struct AppDateTime {
     year: i32,
     month: u32,
     day: u32,
     orig: i64,
-    human_readable: String,
+    locale: Locale,
     machine_friendly: String,
 }
 
-#[cfg(target_arch = "wasm32")]
-impl UserLocalDateTime {}
-
-#[cfg(target_arch = "wasm32")]
-impl DateTime for UserLocalDateTime {
-    fn from_timestamp(timestamp: i64) -> UserLocalDateTime {
-        // Use browser's Date API via js_sys
-        // Create date from timestamp (milliseconds)
-        let timestamp_ms = (timestamp * 1000) as f64; // Convert seconds to milliseconds
-        let js_date = js_sys::Date::new(&timestamp_ms.into());
-
-        let year = js_date.get_full_year() as i32;
-        // JavaScript getMonth() returns 0-11, so we add 1
-        let month = (js_date.get_month() + 1) as u32;
-        let day = js_date.get_date() as u32;
-
-        // Format human readable: YYYY-MM-DD
-        let human_readable = format!("{:04}-{:02}-{:02}", year, month, day);
+impl AppDateTime {
+    /// Convert a unix timestamp to a date in `timezone`, DST included.
+    pub fn from_timestamp(timestamp: i64, timezone: Tz, locale: Locale) -> AppDateTime {
+        let utc_time = Utc.timestamp_opt(timestamp, 0).single().unwrap_or_default();
+        let tz_time = utc_time.with_timezone(&timezone);
 
-        // Format machine friendly: ISO 8601 string
-        let machine_friendly = js_date.to_iso_string().as_string().unwrap_or_default();
-
-        UserLocalDateTime {
-            year,
-            month,
-            day,
+        AppDateTime {
+            year: tz_time.year(),
+            month: tz_time.month(),
+            day: tz_time.day(),
             orig: timestamp,
-            human_readable,
-            machine_friendly,
+            locale,
+            machine_friendly: tz_time.to_rfc3339(),
         }
     }
 
-    fn year(&self) -> i32 {
+    pub fn year(&self) -> i32 {
         self.year
     }
 
-    fn month(&self) -> u32 {
-        self.month
-    }
-
-    fn day(&self) -> u32 {
-        self.day
+    pub fn orig(&self) -> i64 {
+        self.orig
     }
 
-    fn orig(&self) -> i64 {
-        self.orig
+    pub fn human_readable(&self) -> String {
+        self.locale.format_date(self.year, self.month, self.day)
     }
 
-    fn human_readable(&self) -> &str {
-        &self.human_readable
+    pub fn month_day(&self) -> String {
+        self.locale.format_month_day(self.month, self.day)
     }
 
-    fn machine_friendly(&self) -> &str {
+    pub fn machine_friendly(&self) -> &str {
         &self.machine_friendly
     }
 }
 
-#[cfg(target_arch = "wasm32")]
-impl PartialEq for UserLocalDateTime {
+impl PartialEq for AppDateTime {
     fn eq(&self, other: &Self) -> bool {
         self.orig() == other.orig()
     }
 }
 
-#[cfg(target_arch = "wasm32")]
-impl Eq for UserLocalDateTime {}
+impl Eq for AppDateTime {}
 
-#[cfg(target_arch = "wasm32")]
-impl PartialOrd for UserLocalDateTime {
+impl PartialOrd for AppDateTime {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         Some(self.cmp(other))
     }
 }
 
-#[cfg(target_arch = "wasm32")]
-impl Ord for UserLocalDateTime {
+impl Ord for AppDateTime {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         self.orig().cmp(&other.orig())
     }
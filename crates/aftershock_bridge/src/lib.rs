@@ -11,6 +11,13 @@ pub struct Post {
     pub body: String,
     pub summary: Option<String>,
     pub published: bool,
+    pub revision: i32,
+    pub visibility: String,
+    #[serde(default)]
+    pub nav: Option<PageNav>,
+    /// Headings of the body, in order
+    #[serde(default)]
+    pub toc: Vec<TocEntry>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -21,6 +28,33 @@ pub struct NewPost {
     pub tags: Vec<String>,
     pub published: bool,
     pub summary: Option<String>,
+    #[serde(default)]
+    pub visibility: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    #[serde(default)]
+    pub nav: Option<PageNav>,
+    #[serde(default)]
+    pub toc: Vec<TocEntry>,
+}
+
+/// A heading of a post body, `id` is the anchor it can be linked at.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct TocEntry {
+    /// 1 for `<h1>` to 6 for `<h6>`
+    pub level: u8,
+    pub id: String,
+    pub title: String,
+}
+
+/// Where a page shows up in the site navigation.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct PageNav {
+    /// Position in the navigation, lowest first
+    pub order: i32,
+    /// Label shown instead of the page title
+    #[serde(default)]
+    pub title: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -33,6 +67,10 @@ pub struct PostMeta {
     pub tags: Vec<String>,
     pub summary: Option<String>,
     pub published: bool,
+    pub revision: i32,
+    pub visibility: String,
+    #[serde(default)]
+    pub nav: Option<PageNav>,
 }
 
 impl From<Post> for PostMeta {
@@ -46,10 +84,63 @@ impl From<Post> for PostMeta {
             tags: value.tags,
             summary: value.summary,
             published: value.published,
+            revision: value.revision,
+            visibility: value.visibility,
+            nav: value.nav,
         }
     }
 }
 
+/// One page of a listing, `page` counts from 1.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Paged<T> {
+    pub items: Vec<T>,
+    pub page: i64,
+    pub per_page: i64,
+    pub total: i64,
+    pub total_pages: i64,
+}
+
+/// A tag and how many listed posts carry it.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct TagCount {
+    pub tag: String,
+    pub count: i64,
+}
+
+/// Number of listed posts created in a month of the site timezone.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct ArchiveMonth {
+    pub year: i32,
+    pub month: u32,
+    pub count: i64,
+}
+
+/// Where to read next from a post: its chronological neighbours and the posts sharing
+/// the most tags with it.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PostNeighbors {
+    /// The post published just before
+    pub prev: Option<PostMeta>,
+    /// The post published just after
+    pub next: Option<PostMeta>,
+    pub related: Vec<PostMeta>,
+}
+
+/// A post matching a search, with the part of its text around the match.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SearchHit {
+    pub post: PostMeta,
+    pub snippet: Vec<SnippetPart>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct SnippetPart {
+    pub text: String,
+    /// Whether this part is one of the searched terms
+    pub highlight: bool,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct UpdatePost {
     #[serde(default)]
@@ -58,4 +149,46 @@ pub struct UpdatePost {
     pub body: Option<String>,
     #[serde(default)]
     pub published: Option<bool>,
+    #[serde(default)]
+    pub visibility: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    /// `null` takes the content out of the navigation, leaving it out keeps it as is
+    #[serde(
+        default,
+        deserialize_with = "double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub nav: Option<Option<PageNav>>,
+    /// Sent together with `body`, which it describes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub toc: Option<Vec<TocEntry>>,
+}
+
+fn double_option<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UnlockPost {
+    pub password: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ContentEvent {
+    pub id: i32,
+    pub event: String,
+    pub uid: String,
+    pub kind: String,
+    pub timestamp: i64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PreviewToken {
+    pub token: String,
+    pub expires_at: i64,
 }
@@ -4,6 +4,12 @@ use clap::{Parser, Subcommand};
 #[command(version, about)]
 #[command(propagate_version = true)]
 pub struct Cli {
+    /// Profile from the config file to talk to
+    #[arg(long, global = true)]
+    pub profile: Option<String>,
+    /// Storage API base URL, overrides the profile
+    #[arg(long, global = true)]
+    pub api: Option<String>,
     #[command(subcommand)]
     pub command: KindCommands,
 }
@@ -22,6 +28,16 @@ pub enum KindCommands {
         #[command(subcommand)]
         command: Commands,
     },
+    /// Manage server profiles
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+    /// Syntax highlighting themes
+    Theme {
+        #[command(subcommand)]
+        command: ThemeCommands,
+    },
 }
 
 #[derive(Subcommand)]
@@ -53,6 +69,9 @@ pub enum Commands {
         path: String,
         /// The uid of the content
         id: String,
+        /// Overwrite even if the content changed since it was last fetched, or was never fetched
+        #[arg(long)]
+        force: bool,
     },
     /// Publish a content
     #[command(visible_alias = "pub")]
@@ -60,4 +79,85 @@ pub enum Commands {
         /// The uid of the content
         id: String,
     },
+    /// Print a link to review a content before it is published
+    Preview {
+        /// The uid of the content
+        id: String,
+        /// Seconds until the link expires, the server default when omitted
+        #[arg(long)]
+        ttl: Option<u64>,
+    },
+    /// Deleted contents waiting to be purged
+    Trash {
+        #[command(subcommand)]
+        command: TrashCommands,
+    },
+    /// Restore a deleted content from the trash
+    Restore {
+        /// The uid of the content
+        id: String,
+    },
+    /// Permanently remove a deleted content from the trash
+    Purge {
+        /// The uid of the content
+        id: String,
+    },
+}
+
+#[derive(Subcommand)]
+#[command(arg_required_else_help(true))]
+pub enum TrashCommands {
+    /// List deleted contents
+    #[command(visible_alias = "ls")]
+    List,
+}
+
+#[derive(Subcommand)]
+#[command(arg_required_else_help(true))]
+pub enum ConfigCommands {
+    /// List profiles, the default one is marked with `*`
+    #[command(visible_alias = "ls")]
+    List,
+    /// Create or change a profile
+    Set {
+        /// The name of the profile
+        name: String,
+        /// Storage API base URL
+        #[arg(long)]
+        api: Option<String>,
+        /// Public site URL
+        #[arg(long)]
+        site: Option<String>,
+    },
+    /// Remove a profile
+    #[command(visible_alias = "rm")]
+    Remove {
+        /// The name of the profile
+        name: String,
+    },
+    /// Use a profile when `--profile` is not given
+    Use {
+        /// The name of the profile
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+#[command(arg_required_else_help(true))]
+pub enum ThemeCommands {
+    /// List the embedded themes
+    #[command(visible_alias = "ls")]
+    List,
+    /// Print the stylesheet coloring highlighted code blocks
+    Css {
+        /// One theme for both color schemes, instead of `--light` and `--dark`
+        #[arg(long, conflicts_with_all = ["light", "dark"])]
+        theme: Option<String>,
+        /// Theme of the light color scheme
+        #[arg(long, default_value = "InspiredGitHub")]
+        light: String,
+        /// Theme of the dark color scheme
+        #[arg(long, default_value = "Nord")]
+        dark: String,
+    },
 }
@@ -0,0 +1,146 @@
+//! Named server profiles kept in `~/.config/aftershock/config.toml`.
+//!
+//! ```toml
+//! default = "local"
+//!
+//! [profiles.local]
+//! api = "http://127.0.0.1:3030/api/v1"
+//! site = "http://127.0.0.1:3000"
+//! ```
+
+use std::{collections::BTreeMap, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+const LOCAL_API: &str = "http://127.0.0.1:3030/api/v1";
+const LOCAL_SITE: &str = "http://127.0.0.1:3000";
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct Config {
+    /// Profile used when `--profile` is not given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Profile {
+    /// Base URL of the storage API, `/api/v1` included
+    pub api: String,
+    /// Public URL of the site, used for the links the CLI prints
+    #[serde(default = "default_site")]
+    pub site: String,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self {
+            api: LOCAL_API.into(),
+            site: LOCAL_SITE.into(),
+        }
+    }
+}
+
+fn default_site() -> String {
+    LOCAL_SITE.into()
+}
+
+fn config_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|dir| dir.join(".config").join("aftershock").join("config.toml"))
+}
+
+fn fail(msg: String) -> ! {
+    eprintln!("{msg}");
+    std::process::exit(1);
+}
+
+impl Config {
+    pub fn load() -> Self {
+        let Some(text) = config_path().and_then(|path| std::fs::read_to_string(path).ok()) else {
+            return Config::default();
+        };
+        toml::from_str(&text).unwrap_or_else(|e| fail(format!("Invalid config file: {e}")))
+    }
+
+    fn save(&self) {
+        let path = config_path().unwrap_or_else(|| fail("No home directory".into()));
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+        std::fs::write(path, toml::to_string_pretty(self).unwrap()).unwrap();
+    }
+
+    /// Pick the profile named on the command line, then the default one, then the
+    /// built-in local one. `api` overrides whatever the profile says.
+    pub fn resolve(&self, profile: Option<&str>, api: Option<String>) -> Profile {
+        let mut ret = match profile.or(self.default.as_deref()) {
+            Some(name) => self
+                .profiles
+                .get(name)
+                .cloned()
+                .unwrap_or_else(|| fail(format!("Unknown profile {name}"))),
+            None => Profile::default(),
+        };
+        if let Some(api) = api {
+            ret.api = api;
+        }
+        ret
+    }
+}
+
+pub fn list() -> String {
+    let config = Config::load();
+    if config.profiles.is_empty() {
+        return format!("No profiles, using {LOCAL_API}");
+    }
+
+    config
+        .profiles
+        .iter()
+        .map(|(name, profile)| {
+            let mark = match config.default.as_deref() == Some(name) {
+                true => "*",
+                false => " ",
+            };
+            format!("{mark} {name}\t{}\t{}", profile.api, profile.site)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn set(name: String, api: Option<String>, site: Option<String>) -> String {
+    let mut config = Config::load();
+    let profile = config.profiles.entry(name.clone()).or_default();
+    if let Some(api) = api {
+        profile.api = api;
+    }
+    if let Some(site) = site {
+        profile.site = site;
+    }
+    let ret = format!("{name}\t{}\t{}", profile.api, profile.site);
+    config.save();
+    ret
+}
+
+pub fn remove(name: String) -> String {
+    let mut config = Config::load();
+    if config.profiles.remove(&name).is_none() {
+        fail(format!("Unknown profile {name}"));
+    }
+    if config.default.as_deref() == Some(&name) {
+        config.default = None;
+    }
+    config.save();
+    format!("Removed {name}")
+}
+
+pub fn use_default(name: String) -> String {
+    let mut config = Config::load();
+    if !config.profiles.contains_key(&name) {
+        fail(format!("Unknown profile {name}"));
+    }
+    config.default = Some(name.clone());
+    config.save();
+    format!("Using {name} by default")
+}
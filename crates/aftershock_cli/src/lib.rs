@@ -1,3 +1,5 @@
 pub mod command;
+pub mod config;
 pub mod parser;
 pub mod requests;
+pub mod revisions;
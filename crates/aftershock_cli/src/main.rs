@@ -1,11 +1,24 @@
 use aftershock_cli::command::Cli;
 use aftershock_cli::command::Commands;
+use aftershock_cli::command::ConfigCommands;
 use aftershock_cli::command::KindCommands;
+use aftershock_cli::command::ThemeCommands;
+use aftershock_cli::command::TrashCommands;
+use aftershock_cli::config::{self, Config};
+use aftershock_cli::parser::highlighter::{single_theme_css, theme_by_name, theme_css};
 use aftershock_cli::requests::*;
 use clap::Parser;
+use two_face::theme::{EmbeddedLazyThemeSet, EmbeddedThemeName};
 
 fn main() {
     let cli = Cli::parse();
+    // Only commands talking to the API need a profile, `config` has to work to fix a bad one
+    if matches!(
+        cli.command,
+        KindCommands::Article { .. } | KindCommands::Page { .. }
+    ) {
+        use_profile(Config::load().resolve(cli.profile.as_deref(), cli.api));
+    }
 
     match cli.command {
         KindCommands::Article { command } => {
@@ -15,8 +28,16 @@ fn main() {
                 Commands::List => println!("{}", list(kind)),
                 Commands::View { id } => println!("{}", view(kind, id)),
                 Commands::Delete { id } => println!("{}", delete(kind, id)),
-                Commands::Update { path, id } => println!("{}", update(kind, path, id)),
+                Commands::Update { path, id, force } => {
+                    println!("{}", update(kind, path, id, force))
+                }
                 Commands::Publish { id } => println!("{}", publish(kind, id)),
+                Commands::Preview { id, ttl } => println!("{}", preview(kind, id, ttl)),
+                Commands::Trash {
+                    command: TrashCommands::List,
+                } => println!("{}", trash_list(kind)),
+                Commands::Restore { id } => println!("{}", restore(kind, id)),
+                Commands::Purge { id } => println!("{}", purge(kind, id)),
             }
         }
         KindCommands::Page { command } => {
@@ -26,9 +47,44 @@ fn main() {
                 Commands::List => println!("{}", list(kind)),
                 Commands::View { id } => println!("{}", view(kind, id)),
                 Commands::Delete { id } => println!("{}", delete(kind, id)),
-                Commands::Update { path, id } => println!("{}", update(kind, path, id)),
+                Commands::Update { path, id, force } => {
+                    println!("{}", update(kind, path, id, force))
+                }
                 Commands::Publish { id } => println!("{}", publish(kind, id)),
+                Commands::Preview { id, ttl } => println!("{}", preview(kind, id, ttl)),
+                Commands::Trash {
+                    command: TrashCommands::List,
+                } => println!("{}", trash_list(kind)),
+                Commands::Restore { id } => println!("{}", restore(kind, id)),
+                Commands::Purge { id } => println!("{}", purge(kind, id)),
             }
         }
+        KindCommands::Config { command } => match command {
+            ConfigCommands::List => println!("{}", config::list()),
+            ConfigCommands::Set { name, api, site } => println!("{}", config::set(name, api, site)),
+            ConfigCommands::Remove { name } => println!("{}", config::remove(name)),
+            ConfigCommands::Use { name } => println!("{}", config::use_default(name)),
+        },
+        KindCommands::Theme { command } => match command {
+            ThemeCommands::List => {
+                for theme in EmbeddedLazyThemeSet::theme_names() {
+                    println!("{theme}");
+                }
+            }
+            ThemeCommands::Css { theme, light, dark } => match theme {
+                Some(theme) => print!("{}", single_theme_css(embedded_theme(&theme))),
+                None => print!(
+                    "{}",
+                    theme_css(embedded_theme(&light), embedded_theme(&dark))
+                ),
+            },
+        },
     }
 }
+
+fn embedded_theme(name: &str) -> EmbeddedThemeName {
+    theme_by_name(name).unwrap_or_else(|| {
+        eprintln!("No theme called {name}, see `aftershock_cli theme ls`");
+        std::process::exit(1);
+    })
+}
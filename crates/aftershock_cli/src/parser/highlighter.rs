@@ -1,32 +1,132 @@
+use std::fmt::Write;
+
 use pulldown_cmark::{CodeBlockKind, CowStr, Event, Tag, TagEnd};
 use two_face::{
-    re_exports::syntect::html,
-    re_exports::syntect::parsing::SyntaxSet,
+    re_exports::syntect::html::{
+        ClassStyle, css_for_theme_with_class_style, line_tokens_to_classed_spans,
+    },
+    re_exports::syntect::parsing::{ParseState, Scope, ScopeStack, SyntaxSet},
+    re_exports::syntect::util::LinesWithEndings,
     theme::{EmbeddedLazyThemeSet, EmbeddedThemeName},
 };
 
+/// Highlights code blocks into spans of syntect scope classes, prefixed with `hl-`. The
+/// colors live in a stylesheet, see [`theme_css`], so the theme of a stored body can still
+/// change.
+///
+/// Every line is a `hl-line` span of its own, closing and reopening the scopes it shares
+/// with the next, so lines can be numbered, marked or styled as a diff.
 pub struct Highlighter {
     syntax_set: SyntaxSet,
-    theme_set: EmbeddedLazyThemeSet,
-    theme: EmbeddedThemeName,
+}
+
+const CLASS_PREFIX: &str = "hl-";
+const CLASS_STYLE: ClassStyle = ClassStyle::SpacedPrefixed {
+    prefix: CLASS_PREFIX,
+};
+
+/// What the info string of a fenced code block asks for, such as
+/// `rust title="main.rs" {3,5-7} linenos`.
+#[derive(Debug, Default, PartialEq)]
+struct CodeInfo {
+    /// The first word, unless it is an option
+    lang: Option<String>,
+    title: Option<String>,
+    /// Ranges of lines to mark, from 1 and inclusive
+    marked: Vec<(usize, usize)>,
+    line_numbers: bool,
+}
+
+impl CodeInfo {
+    fn parse(info: &str) -> Self {
+        let mut code_info = CodeInfo::default();
+        for (n, word) in words(info).into_iter().enumerate() {
+            if let Some(ranges) = word.strip_prefix('{') {
+                let ranges = ranges.strip_suffix('}').unwrap_or(ranges);
+                code_info
+                    .marked
+                    .extend(ranges.split(',').filter_map(line_range));
+            } else if let Some((key, value)) = word.split_once('=') {
+                if key == "title" && !value.is_empty() {
+                    code_info.title = Some(value.to_string());
+                }
+            } else if word == "linenos" {
+                code_info.line_numbers = true;
+            } else if n == 0 {
+                code_info.lang = Some(word);
+            }
+        }
+        code_info
+    }
+
+    fn is_marked(&self, line: usize) -> bool {
+        self.marked
+            .iter()
+            .any(|(start, end)| (*start..=*end).contains(&line))
+    }
+}
+
+/// The words of an info string. Quotes keep spaces in a word and are dropped, braces keep
+/// a range list together and start a word of their own, so `rust{3}` is `rust` and `{3}`.
+fn words(info: &str) -> Vec<String> {
+    let mut words = vec![];
+    let mut word = String::new();
+    let mut quote = None;
+    let mut in_braces = false;
+    for c in info.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => word.push(c),
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '{') => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+                in_braces = true;
+                word.push(c);
+            }
+            (None, '}') => {
+                in_braces = false;
+                word.push(c);
+            }
+            (None, c) if c.is_whitespace() && !in_braces => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+            }
+            (None, c) if c.is_whitespace() => {}
+            (None, c) => word.push(c),
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// `3` or `5-7`.
+fn line_range(range: &str) -> Option<(usize, usize)> {
+    match range.trim().split_once('-') {
+        Some((start, end)) => Some((start.trim().parse().ok()?, end.trim().parse().ok()?)),
+        None => range.trim().parse().ok().map(|line| (line, line)),
+    }
+}
+
+impl Default for Highlighter {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Highlighter {
-    pub fn new(theme: EmbeddedThemeName) -> Self {
+    pub fn new() -> Self {
         let syntax_set = two_face::syntax::extra_newlines();
-        let theme_set = two_face::theme::extra();
 
-        Self {
-            syntax_set,
-            theme_set,
-            theme,
-        }
+        Self { syntax_set }
     }
 
     pub fn highlight<'e, IE: Iterator<Item = Event<'e>>>(&self, events: IE) -> Vec<Event<'e>> {
-        let theme = self.theme_set.get(self.theme);
-        let mut syntax = self.syntax_set.find_syntax_plain_text();
-        let mut inside_code = false;
+        let mut info = None;
 
         let mut ret = vec![];
 
@@ -34,29 +134,22 @@ impl Highlighter {
         for event in events {
             match event {
                 Event::Start(Tag::CodeBlock(kind)) => {
-                    if let CodeBlockKind::Fenced(lang) = kind {
-                        syntax = self
-                            .syntax_set
-                            .find_syntax_by_token(&lang)
-                            .unwrap_or(syntax);
-                    }
-                    inside_code = true
+                    info = Some(match kind {
+                        CodeBlockKind::Fenced(info) => CodeInfo::parse(&info),
+                        CodeBlockKind::Indented => CodeInfo::default(),
+                    });
                 }
                 Event::End(TagEnd::CodeBlock) => {
-                    if !inside_code {
+                    let Some(info) = info.take() else {
                         unreachable!("Run into CodeBlock end without a CodeBlock start")
-                    }
-
-                    let html =
-                        html::highlighted_html_for_string(&buffer, &self.syntax_set, syntax, theme)
-                            .unwrap();
+                    };
 
+                    let html = self.render(&info, &buffer);
                     buffer.clear();
-                    inside_code = false;
                     ret.push(Event::Html(CowStr::from(html)));
                 }
                 Event::Text(text) => {
-                    if inside_code {
+                    if info.is_some() {
                         buffer.push_str(&text);
                     } else {
                         ret.push(Event::Text(text));
@@ -70,4 +163,306 @@ impl Highlighter {
 
         ret
     }
+
+    /// A code block as a `hl-block` figure, with its title as the caption.
+    fn render(&self, info: &CodeInfo, code: &str) -> String {
+        // `diff` is highlighted as a diff, `diff-rust` as Rust after the `+`, `-` or space
+        let (diff, lang) = match info.lang.as_deref() {
+            Some("diff") => (true, Some("diff")),
+            Some(lang) => match lang.strip_prefix("diff-") {
+                Some(lang) => (true, Some(lang)),
+                None => (false, Some(lang)),
+            },
+            None => (false, None),
+        };
+        let split_marker = diff && lang != Some("diff");
+        let syntax = lang
+            .and_then(|lang| self.syntax_set.find_syntax_by_token(lang))
+            .unwrap_or_else(|| self.syntax_set.find_syntax_plain_text());
+
+        let lines = LinesWithEndings::from(code).collect::<Vec<_>>();
+        let mut html = String::from("<figure class=\"hl-block\">");
+        if let Some(title) = &info.title {
+            write!(
+                html,
+                "<figcaption class=\"hl-title\">{}</figcaption>",
+                escape(title)
+            )
+            .unwrap();
+        }
+        if info.line_numbers {
+            let digits = lines.len().to_string().len();
+            write!(
+                html,
+                "<pre class=\"hl-code hl-numbered\" style=\"--hl-digits:{digits}\">"
+            )
+            .unwrap();
+        } else {
+            html.push_str("<pre class=\"hl-code\">");
+        }
+        html.push_str("<code>");
+
+        let mut state = ParseState::new(syntax);
+        let mut stack = ScopeStack::new();
+        for (n, line) in lines.into_iter().enumerate() {
+            let mut class = String::from("hl-line");
+            if diff {
+                match line.chars().next() {
+                    Some('+') => class.push_str(" hl-added"),
+                    Some('-') => class.push_str(" hl-removed"),
+                    _ => {}
+                }
+            }
+            if info.is_marked(n + 1) {
+                class.push_str(" hl-marked");
+            }
+            write!(html, "<span class=\"{class}\" data-line=\"{}\">", n + 1).unwrap();
+
+            let (marker, line) = match line.chars().next() {
+                Some('+' | '-' | ' ') if split_marker => line.split_at(1),
+                _ => ("", line),
+            };
+            html.push_str(marker);
+            for scope in stack.as_slice() {
+                open_span(&mut html, *scope);
+            }
+            let ops = state.parse_line(line, &self.syntax_set).unwrap();
+            let (spans, _) =
+                line_tokens_to_classed_spans(line, &ops, CLASS_STYLE, &mut stack).unwrap();
+            html.push_str(&spans);
+            html.push_str(&"</span>".repeat(stack.as_slice().len() + 1));
+        }
+
+        html.push_str("</code></pre></figure>\n");
+        html
+    }
+}
+
+/// The `<span>` syntect opens for `scope`.
+fn open_span(html: &mut String, scope: Scope) {
+    let classes = scope
+        .build_string()
+        .split('.')
+        .map(|atom| format!("{CLASS_PREFIX}{atom}"))
+        .collect::<Vec<_>>();
+    write!(html, "<span class=\"{}\">", classes.join(" ")).unwrap();
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// The embedded theme called `name`, ignoring case.
+pub fn theme_by_name(name: &str) -> Option<EmbeddedThemeName> {
+    EmbeddedLazyThemeSet::theme_names()
+        .iter()
+        .copied()
+        .find(|theme| theme.as_name().eq_ignore_ascii_case(name))
+}
+
+/// The stylesheet of one theme, for the classes [`Highlighter`] writes.
+pub fn single_theme_css(theme: EmbeddedThemeName) -> String {
+    css_for_theme_with_class_style(two_face::theme::extra().get(theme), CLASS_STYLE).unwrap()
+}
+
+/// The stylesheet of a light and a dark theme. The dark one applies under the `dark` class
+/// of `<html>`, or the system dark color scheme when `<html>` is not `light`, the same way
+/// as the theme toggle of the frontend.
+pub fn theme_css(light: EmbeddedThemeName, dark: EmbeddedThemeName) -> String {
+    let dark = single_theme_css(dark);
+    format!(
+        "{}\n:root.dark {{\n{dark}}}\n\n@media (prefers-color-scheme: dark) {{\n:root:not(.light) {{\n{dark}}}\n}}\n",
+        single_theme_css(light)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn highlight(lang: &str, code: &str) -> String {
+        let info = CodeInfo {
+            lang: Some(lang.into()),
+            ..Default::default()
+        };
+        Highlighter::new().render(&info, code)
+    }
+
+    /// The `hl-line` spans of a rendered block.
+    fn lines(html: &str) -> Vec<&str> {
+        html.split("<span class=\"hl-line")
+            .skip(1)
+            .map(|line| line.split("</code>").next().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_classes_are_prefixed() {
+        let html = highlight("rust", "fn main() {}\n");
+        assert!(html.starts_with("<figure class=\"hl-block\"><pre class=\"hl-code\"><code>"));
+        assert!(html.contains("hl-source hl-rust"));
+        assert!(html.contains("hl-storage hl-type hl-function"));
+        assert!(!html.contains("style=\"color"));
+    }
+
+    #[test]
+    fn test_every_line_is_balanced() {
+        // The block comment spans lines, so its scope is closed and reopened on each
+        let html = highlight("rust", "/* one\ntwo\nthree */\nlet x = 1;\n");
+        let lines = lines(&html);
+        assert_eq!(lines.len(), 4);
+        for line in &lines {
+            assert_eq!(
+                line.matches("<span").count() + 1,
+                line.matches("</span>").count(),
+                "{line}"
+            );
+        }
+        assert!(lines[1].contains("hl-comment"));
+        assert!(lines[1].contains("data-line=\"2\""));
+    }
+
+    #[test]
+    fn test_code_is_escaped() {
+        let html = highlight("html", "<b>&</b>\n");
+        assert!(html.contains("&lt;"));
+        assert!(html.contains("&amp;"));
+        assert!(!html.contains("<b>"));
+    }
+
+    #[test]
+    fn test_unknown_lang_is_plain_text() {
+        let html = highlight("no-such-lang", "a < b\n");
+        assert!(html.contains("hl-text hl-plain"));
+        assert!(html.contains("a &lt; b"));
+    }
+
+    #[test]
+    fn test_theme_css() {
+        let light = theme_by_name("inspiredgithub").unwrap();
+        let dark = theme_by_name("Nord").unwrap();
+        let css = theme_css(light, dark);
+        assert!(css.contains(".hl-storage"));
+        assert!(css.contains(":root.dark {"));
+        assert!(css.contains(":root:not(.light) {"));
+    }
+
+    fn code_info(lang: Option<&str>, title: Option<&str>, marked: &[(usize, usize)]) -> CodeInfo {
+        CodeInfo {
+            lang: lang.map(Into::into),
+            title: title.map(Into::into),
+            marked: marked.to_vec(),
+            line_numbers: false,
+        }
+    }
+
+    #[test]
+    fn test_line_range() {
+        assert_eq!(line_range("3"), Some((3, 3)));
+        assert_eq!(line_range(" 5 - 7 "), Some((5, 7)));
+        assert_eq!(line_range(""), None);
+        assert_eq!(line_range("5-"), None);
+        assert_eq!(line_range("a-b"), None);
+    }
+
+    #[test]
+    fn test_words() {
+        assert_eq!(
+            words("rust title=\"main file.rs\""),
+            ["rust", "title=main file.rs"]
+        );
+        assert_eq!(words("rust title='a \"b\"'"), ["rust", "title=a \"b\""]);
+        assert_eq!(
+            words("rust{3, 5-7} linenos"),
+            ["rust", "{3,5-7}", "linenos"]
+        );
+        assert_eq!(words("  rust   {1}  "), ["rust", "{1}"]);
+    }
+
+    #[test]
+    fn test_parse_info() {
+        assert_eq!(CodeInfo::parse(""), CodeInfo::default());
+        assert_eq!(CodeInfo::parse("rust"), code_info(Some("rust"), None, &[]));
+        assert_eq!(
+            CodeInfo::parse("rust title=\"src/main.rs\" {1,3-5} linenos"),
+            CodeInfo {
+                line_numbers: true,
+                ..code_info(Some("rust"), Some("src/main.rs"), &[(1, 1), (3, 5)])
+            }
+        );
+        assert_eq!(
+            CodeInfo::parse("rust title=main.rs"),
+            code_info(Some("rust"), Some("main.rs"), &[])
+        );
+        assert_eq!(
+            CodeInfo::parse("rust{3}"),
+            code_info(Some("rust"), None, &[(3, 3)])
+        );
+        assert_eq!(
+            CodeInfo::parse("diff-rust"),
+            code_info(Some("diff-rust"), None, &[])
+        );
+    }
+
+    #[test]
+    fn test_parse_info_without_lang() {
+        // Options in the first place are options, not a language
+        assert_eq!(CodeInfo::parse("{2}"), code_info(None, None, &[(2, 2)]));
+        assert_eq!(
+            CodeInfo::parse("title=a.txt"),
+            code_info(None, Some("a.txt"), &[])
+        );
+        assert_eq!(CodeInfo::parse("linenos").lang, None);
+        // An empty title, an unknown option or a bad range is ignored
+        assert_eq!(
+            CodeInfo::parse("sh title= mode=x {x,2}"),
+            code_info(Some("sh"), None, &[(2, 2)])
+        );
+    }
+
+    #[test]
+    fn test_render_title_and_line_numbers() {
+        let info = CodeInfo::parse("text title=\"<a> & b\" linenos");
+        let code = "1\n".repeat(10);
+        let html = Highlighter::new().render(&info, &code);
+        assert!(html.contains("<figcaption class=\"hl-title\">&lt;a&gt; &amp; b</figcaption>"));
+        assert!(html.contains("<pre class=\"hl-code hl-numbered\" style=\"--hl-digits:2\">"));
+        assert!(html.contains("data-line=\"10\""));
+    }
+
+    #[test]
+    fn test_render_marked() {
+        let html = Highlighter::new().render(&CodeInfo::parse("text {2-3}"), "a\nb\nc\nd\n");
+        let marked = lines(&html)
+            .iter()
+            .map(|line| line.starts_with(" hl-marked"))
+            .collect::<Vec<_>>();
+        assert_eq!(marked, [false, true, true, false]);
+    }
+
+    #[test]
+    fn test_render_diff() {
+        let html = Highlighter::new().render(&CodeInfo::parse("diff"), "+a\n-b\n c\n");
+        let lines = lines(&html);
+        assert!(lines[0].starts_with(" hl-added\""));
+        assert!(lines[1].starts_with(" hl-removed\""));
+        assert!(lines[2].starts_with("\""));
+        assert!(html.contains("hl-source hl-diff"));
+    }
+
+    #[test]
+    fn test_render_diff_lang() {
+        let html =
+            Highlighter::new().render(&CodeInfo::parse("diff-rust {1}"), "+fn a() {}\n-let b;\n");
+        let lines = lines(&html);
+        assert!(lines[0].starts_with(" hl-added hl-marked\""));
+        // The marker stays out of the Rust scopes and the rest is highlighted as Rust
+        assert!(lines[0].contains("data-line=\"1\">+<span class=\"hl-source hl-rust\">"));
+        assert!(lines[1].contains("data-line=\"2\">-<span class=\"hl-source hl-rust\">"));
+        assert!(lines[1].contains("hl-storage"));
+        assert!(!html.contains("hl-source hl-diff"));
+    }
 }
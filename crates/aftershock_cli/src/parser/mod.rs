@@ -3,9 +3,9 @@ use std::sync::LazyLock;
 use highlighter::Highlighter;
 use pulldown_cmark::{Event, MetadataBlockKind, Options, Parser, Tag, TagEnd};
 use serde::{Deserialize, Serialize};
-use two_face::theme::EmbeddedThemeName;
 
-mod highlighter;
+pub mod highlighter;
+mod outline;
 
 static OPTIONS: LazyLock<Options> = LazyLock::new(get_options);
 
@@ -13,16 +13,26 @@ static OPTIONS: LazyLock<Options> = LazyLock::new(get_options);
 pub struct ParserOutput {
     pub metadata: ParserOutputMetadata,
     pub html: String,
+    pub toc: Vec<aftershock_bridge::TocEntry>,
 }
 
 impl ParserOutput {
-    pub fn new(metadata: ParserOutputMetadata, html: String) -> Self {
-        Self { metadata, html }
+    pub fn new(
+        metadata: ParserOutputMetadata,
+        html: String,
+        toc: Vec<aftershock_bridge::TocEntry>,
+    ) -> Self {
+        Self {
+            metadata,
+            html,
+            toc,
+        }
     }
 }
 
 impl From<ParserOutput> for aftershock_bridge::NewPost {
     fn from(value: ParserOutput) -> Self {
+        let nav = value.metadata.nav();
         Self {
             title: value.metadata.title,
             kind: value.metadata.kind,
@@ -30,6 +40,10 @@ impl From<ParserOutput> for aftershock_bridge::NewPost {
             tags: value.metadata.tags,
             published: false,
             summary: value.metadata.summary,
+            visibility: value.metadata.visibility,
+            password: value.metadata.password,
+            nav,
+            toc: value.toc,
         }
     }
 }
@@ -40,6 +54,27 @@ pub struct ParserOutputMetadata {
     pub kind: String,
     pub tags: Vec<String>,
     pub summary: Option<String>,
+    /// `public`, `unlisted`, `private` or `protected`
+    #[serde(default)]
+    pub visibility: Option<String>,
+    /// Required when `visibility` is `protected`
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Pages with an order are linked from the site navigation
+    #[serde(default)]
+    pub nav_order: Option<i32>,
+    /// Navigation label, the title when missing
+    #[serde(default)]
+    pub nav_title: Option<String>,
+}
+
+impl ParserOutputMetadata {
+    pub fn nav(&self) -> Option<aftershock_bridge::PageNav> {
+        self.nav_order.map(|order| aftershock_bridge::PageNav {
+            order,
+            title: self.nav_title.clone(),
+        })
+    }
 }
 
 fn get_options() -> Options {
@@ -86,10 +121,11 @@ pub fn parse(text: &str) -> ParserOutput {
     let events: Vec<_> = parser.into_iter().collect();
     let metadata = parse_metadata(events.iter());
 
-    let highlighter = Highlighter::new(EmbeddedThemeName::Nord);
+    let highlighter = Highlighter::new();
     let events = highlighter.highlight(events.into_iter());
+    let (events, toc) = outline::outline(events.into_iter());
 
     let mut html = String::new();
     pulldown_cmark::html::push_html(&mut html, events.into_iter());
-    ParserOutput::new(metadata, html)
+    ParserOutput::new(metadata, html, toc)
 }
@@ -0,0 +1,147 @@
+use std::collections::HashSet;
+
+use pulldown_cmark::{CowStr, Event, Tag, TagEnd};
+
+/// Give every heading an `id` and a hover anchor, collecting the headings on the way.
+///
+/// Ids come from the heading text alone, so they survive edits elsewhere in the post.
+/// Letters and digits of any script are kept, CJK headings included.
+pub fn outline<'e, IE: Iterator<Item = Event<'e>>>(
+    events: IE,
+) -> (Vec<Event<'e>>, Vec<aftershock_bridge::TocEntry>) {
+    let mut ret = vec![];
+    let mut toc = vec![];
+    let mut seen = HashSet::new();
+
+    // Index of the heading start in `ret`, its level and text so far
+    let mut heading: Option<(usize, u8, String)> = None;
+
+    for event in events {
+        match event {
+            Event::Start(Tag::Heading { level, .. }) => {
+                heading = Some((ret.len(), level as u8, String::new()));
+                ret.push(event);
+            }
+            Event::Text(ref text) | Event::Code(ref text) if heading.is_some() => {
+                if let Some((_, _, title)) = heading.as_mut() {
+                    title.push_str(text);
+                }
+                ret.push(event);
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                let Some((start, level, title)) = heading.take() else {
+                    unreachable!("Run into Heading end without a Heading start")
+                };
+                let id = unique(&mut seen, slugify(&title));
+
+                if let Event::Start(Tag::Heading { id: heading_id, .. }) = &mut ret[start] {
+                    *heading_id = Some(CowStr::from(id.clone()));
+                }
+                ret.push(Event::InlineHtml(CowStr::from(format!(
+                    "<a class=\"heading-anchor\" href=\"#{id}\" aria-hidden=\"true\">#</a>"
+                ))));
+                ret.push(event);
+
+                toc.push(aftershock_bridge::TocEntry {
+                    level,
+                    id,
+                    title: title.trim().to_owned(),
+                });
+            }
+            ev => ret.push(ev),
+        }
+    }
+
+    (ret, toc)
+}
+
+fn slugify(title: &str) -> String {
+    let mut ret = String::new();
+    for c in title.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            ret.push(c);
+        } else if (c.is_whitespace() || c == '-' || c == '_') && !ret.ends_with('-') {
+            ret.push('-');
+        }
+    }
+
+    match ret.trim_matches('-') {
+        "" => "section".into(),
+        slug => slug.into(),
+    }
+}
+
+/// Number repeated ids the way GitHub does: `intro`, `intro-1`, `intro-2`.
+fn unique(seen: &mut HashSet<String>, slug: String) -> String {
+    let mut ret = slug.clone();
+    let mut count = 1;
+    while !seen.insert(ret.clone()) {
+        ret = format!("{slug}-{count}");
+        count += 1;
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use pulldown_cmark::Parser;
+
+    use super::*;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Getting Started"), "getting-started");
+        assert_eq!(slugify("  What's `new`?  "), "whats-new");
+        assert_eq!(
+            slugify("snake_case -- and  dashes"),
+            "snake-case-and-dashes"
+        );
+        assert_eq!(slugify("Ünïcode Straße"), "ünïcode-straße");
+    }
+
+    #[test]
+    fn test_slugify_keeps_cjk() {
+        assert_eq!(slugify("安装与配置"), "安装与配置");
+        assert_eq!(slugify("第 2 章：开始"), "第-2-章开始");
+        assert_eq!(slugify("はじめに"), "はじめに");
+    }
+
+    #[test]
+    fn test_slugify_without_letters() {
+        assert_eq!(slugify(""), "section");
+        assert_eq!(slugify("!?"), "section");
+    }
+
+    #[test]
+    fn test_unique_numbers_repeats() {
+        let mut seen = HashSet::new();
+        assert_eq!(unique(&mut seen, "intro".into()), "intro");
+        assert_eq!(unique(&mut seen, "intro".into()), "intro-1");
+        assert_eq!(unique(&mut seen, "intro".into()), "intro-2");
+        // A heading that is literally `intro-1` moves on as well
+        assert_eq!(unique(&mut seen, "intro-1".into()), "intro-1-1");
+    }
+
+    #[test]
+    fn test_outline() {
+        let (events, toc) = outline(Parser::new("# Intro\n\n## `Code` 示例\n\n# Intro\n"));
+
+        let entries = toc
+            .iter()
+            .map(|entry| (entry.level, entry.id.as_str(), entry.title.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            entries,
+            [
+                (1, "intro", "Intro"),
+                (2, "code-示例", "Code 示例"),
+                (1, "intro-1", "Intro"),
+            ]
+        );
+
+        let mut html = String::new();
+        pulldown_cmark::html::push_html(&mut html, events.into_iter());
+        assert!(html.contains(r##"<h1 id="intro">Intro<a class="heading-anchor" href="#intro""##));
+        assert!(html.contains(r#"<h1 id="intro-1">"#));
+    }
+}
@@ -1,13 +1,36 @@
-use std::sync::LazyLock;
+use std::sync::{LazyLock, OnceLock};
 
-use ::reqwest::{IntoUrl, blocking::Response, header::CONTENT_TYPE};
+use ::reqwest::{
+    IntoUrl, StatusCode,
+    blocking::Response,
+    header::{CONTENT_TYPE, IF_MATCH},
+};
 use reqwest::blocking as reqwest;
 
-use crate::parser::ParserOutput;
+use crate::{config::Profile, parser::ParserOutput, revisions};
 
-static API_BASE: &str = "http://127.0.0.1:3030/api/v1";
+static PROFILE: OnceLock<Profile> = OnceLock::new();
 static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(reqwest::Client::new);
 
+/// Point every request at `profile`, the built-in local one is used otherwise.
+pub fn use_profile(profile: Profile) {
+    let _ = PROFILE.set(profile);
+}
+
+fn api_base() -> &'static str {
+    PROFILE
+        .get_or_init(Profile::default)
+        .api
+        .trim_end_matches('/')
+}
+
+fn site_base() -> &'static str {
+    PROFILE
+        .get_or_init(Profile::default)
+        .site
+        .trim_end_matches('/')
+}
+
 fn get<U: IntoUrl>(url: U) -> Result<Response, ::reqwest::Error> {
     CLIENT.get(url).send()
 }
@@ -18,7 +41,7 @@ fn parse_from_file(path: &str) -> ParserOutput {
 }
 
 pub fn add(kind: String, path: String) -> String {
-    let url = format!("{API_BASE}/{kind}s");
+    let url = format!("{}/{kind}s", api_base());
     // let input = std::fs::read_to_string(&path).unwrap();
     // let output = crate::parser::parse(&input);
     let output = parse_from_file(&path);
@@ -36,11 +59,12 @@ pub fn add(kind: String, path: String) -> String {
         .unwrap()
         .json::<aftershock_bridge::Post>()
         .unwrap();
+    revisions::remember(api_base(), &body);
     serde_json::to_string_pretty(&body).unwrap()
 }
 
 pub fn list(kind: String) -> String {
-    let url = format!("{API_BASE}/{kind}s/all-meta");
+    let url = format!("{}/{kind}s/all-meta", api_base());
     let body = get(url)
         .unwrap()
         .json::<Vec<aftershock_bridge::PostMeta>>()
@@ -49,18 +73,18 @@ pub fn list(kind: String) -> String {
 }
 
 pub fn view(kind: String, id: String) -> String {
-    let url = format!("{API_BASE}/{kind}s/uid/{id}");
-    let body = get(url);
-    serde_json::to_string_pretty(
-        &body
-            .ok()
-            .map(|body| body.json::<aftershock_bridge::Post>().unwrap()),
-    )
-    .unwrap()
+    let url = format!("{}/{kind}s/uid/{id}", api_base());
+    let body = get(url)
+        .ok()
+        .map(|body| body.json::<aftershock_bridge::Post>().unwrap());
+    if let Some(post) = &body {
+        revisions::remember(api_base(), post);
+    }
+    serde_json::to_string_pretty(&body).unwrap()
 }
 
 pub fn delete(kind: String, id: String) -> String {
-    let url = format!("{API_BASE}/{kind}s/uid/{id}");
+    let url = format!("{}/{kind}s/uid/{id}", api_base());
     let client = &CLIENT;
     let body = client
         .delete(url)
@@ -71,24 +95,48 @@ pub fn delete(kind: String, id: String) -> String {
     serde_json::to_string_pretty(&body).unwrap()
 }
 
-fn send_update_request(url: &str, body: aftershock_bridge::UpdatePost) -> aftershock_bridge::Post {
+fn send_update_request(
+    url: &str,
+    body: aftershock_bridge::UpdatePost,
+    revision: Option<i32>,
+) -> aftershock_bridge::Post {
     let body = serde_json::to_string(&body).unwrap();
-    CLIENT
+    let mut request = CLIENT
         .put(url)
         .header(CONTENT_TYPE, "application/json")
-        .body(body)
-        .send()
-        .unwrap()
-        .json::<aftershock_bridge::Post>()
-        .unwrap()
+        .body(body);
+    if let Some(revision) = revision {
+        request = request.header(IF_MATCH, format!("\"{revision}\""));
+    }
+    let response = request.send().unwrap();
+
+    if response.status() == StatusCode::PRECONDITION_FAILED {
+        let current = response.json::<aftershock_bridge::Post>().unwrap();
+        eprintln!(
+            "Refusing to overwrite {} {}: it is at revision {} on the server, but was at revision {} when last fetched. View it again or pass --force.",
+            current.kind,
+            current.uid,
+            current.revision,
+            revision.unwrap_or_default(),
+        );
+        std::process::exit(1);
+    }
+
+    let post = response.json::<aftershock_bridge::Post>().unwrap();
+    revisions::remember(api_base(), &post);
+    post
 }
 
 pub fn publish(kind: String, id: String) -> String {
-    let url = format!("{API_BASE}/{kind}s/uid/{id}");
+    let url = format!("{}/{kind}s/uid/{id}", api_base());
     let body = aftershock_bridge::UpdatePost {
         title: None,
         body: None,
         published: Some(true),
+        visibility: None,
+        password: None,
+        nav: None,
+        toc: None,
     };
     // let body = serde_json::to_string(&body).unwrap();
     // let post = CLIENT
@@ -99,18 +147,88 @@ pub fn publish(kind: String, id: String) -> String {
     //     .unwrap()
     //     .json::<aftershock_bridge::Post>()
     //     .unwrap();
-    let post = send_update_request(&url, body);
+    let post = send_update_request(&url, body, None);
     serde_json::to_string_pretty(&post).unwrap()
 }
 
-pub fn update(kind: String, path: String, id: String) -> String {
-    let url = format!("{API_BASE}/{kind}s/uid/{id}");
+pub fn update(kind: String, path: String, id: String, force: bool) -> String {
+    let url = format!("{}/{kind}s/uid/{id}", api_base());
     let output = parse_from_file(&path);
+    // Only pages live in the navigation, the front matter is the whole truth about it
+    let nav = match kind.as_str() {
+        "page" => Some(output.metadata.nav()),
+        _ => None,
+    };
     let body = aftershock_bridge::UpdatePost {
         title: Some(output.metadata.title),
         body: Some(output.html),
         published: None,
+        visibility: output.metadata.visibility,
+        password: output.metadata.password,
+        nav,
+        toc: Some(output.toc),
+    };
+    let revision = match force {
+        true => None,
+        false => match revisions::recall(api_base(), &kind, &id) {
+            Some(revision) => Some(revision),
+            None => {
+                // Without a revision the server cannot tell whether this overwrites newer changes
+                eprintln!(
+                    "Refusing to overwrite {kind} {id}: it was never fetched from {} on this machine. View it first or pass --force.",
+                    api_base(),
+                );
+                std::process::exit(1);
+            }
+        },
     };
-    let post = send_update_request(&url, body);
+    let post = send_update_request(&url, body, revision);
+    serde_json::to_string_pretty(&post).unwrap()
+}
+
+pub fn trash_list(kind: String) -> String {
+    let url = format!("{}/{kind}s/trash", api_base());
+    let body = get(url)
+        .unwrap()
+        .json::<Vec<aftershock_bridge::PostMeta>>()
+        .unwrap();
+    serde_json::to_string_pretty(&body).unwrap()
+}
+
+pub fn restore(kind: String, id: String) -> String {
+    let url = format!("{}/{kind}s/trash/{id}/restore", api_base());
+    let post = CLIENT
+        .post(url)
+        .send()
+        .unwrap()
+        .json::<aftershock_bridge::Post>()
+        .unwrap();
     serde_json::to_string_pretty(&post).unwrap()
 }
+
+pub fn purge(kind: String, id: String) -> String {
+    let url = format!("{}/{kind}s/trash/{id}", api_base());
+    let post = CLIENT
+        .delete(url)
+        .send()
+        .unwrap()
+        .json::<aftershock_bridge::Post>()
+        .unwrap();
+    serde_json::to_string_pretty(&post).unwrap()
+}
+
+pub fn preview(kind: String, id: String, ttl: Option<u64>) -> String {
+    let url = match ttl {
+        Some(ttl) => format!("{}/{kind}s/uid/{id}/preview?ttl={ttl}", api_base()),
+        None => format!("{}/{kind}s/uid/{id}/preview", api_base()),
+    };
+    let preview = CLIENT
+        .post(url)
+        .send()
+        .unwrap()
+        .error_for_status()
+        .unwrap()
+        .json::<aftershock_bridge::PreviewToken>()
+        .unwrap();
+    format!("{}/preview/{}", site_base(), preview.token)
+}
@@ -0,0 +1,41 @@
+//! Revisions of the contents last fetched from this machine.
+//!
+//! `update` sends the remembered revision as `If-Match`, so the server refuses to
+//! overwrite a content that somebody else changed in the meantime. Revisions are kept per
+//! storage API, a content fetched from one profile says nothing about another.
+
+use std::{collections::HashMap, path::PathBuf};
+
+fn store_path() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|dir| dir.join("aftershock").join("revisions.json"))
+}
+
+/// The URL of the content, so the same uid on two servers never shares a revision.
+fn key(api: &str, kind: &str, uid: &str) -> String {
+    format!("{api}/{kind}s/uid/{uid}")
+}
+
+fn load() -> HashMap<String, i32> {
+    store_path()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|x| serde_json::from_str(&x).ok())
+        .unwrap_or_default()
+}
+
+pub fn recall(api: &str, kind: &str, uid: &str) -> Option<i32> {
+    load().get(&key(api, kind, uid)).copied()
+}
+
+pub fn remember(api: &str, post: &aftershock_bridge::Post) {
+    let Some(path) = store_path() else {
+        return;
+    };
+    let mut revisions = load();
+    revisions.insert(key(api, &post.kind, &post.uid), post.revision);
+
+    // Losing the store only disables the check, never fail the command because of it
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    let _ = std::fs::write(path, serde_json::to_string_pretty(&revisions).unwrap());
+}
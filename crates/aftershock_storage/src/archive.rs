@@ -0,0 +1,367 @@
+//! Whole-site export and import.
+//!
+//! An archive is a JSON lines file. The first line is a [`Record::Header`] carrying the
+//! format version, every following line is a content, a tag or a link between the two.
+//! Contents are identified by `(kind, uid)` and tags by name, so row ids never leave the
+//! database and an archive can be merged into a database that already has data.
+
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    POOL, Result,
+    error::Error,
+    events::{self, EventKind},
+    models::{ContentKind, NewTag, Visibility},
+    schema::{contents, contents_tags, tags},
+};
+
+pub const ARCHIVE_FORMAT: &str = "aftershock-archive";
+pub const ARCHIVE_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Record {
+    Header {
+        format: String,
+        version: u32,
+        exported_at: i64,
+    },
+    Content(ArchivedContent),
+    Tag {
+        tag: String,
+    },
+    ContentTag {
+        kind: String,
+        uid: String,
+        tag: String,
+    },
+}
+
+#[derive(Queryable, Selectable, Insertable, AsChangeset, Serialize, Deserialize, Debug)]
+#[diesel(table_name = crate::schema::contents, check_for_backend(diesel::sqlite::Sqlite))]
+#[diesel(treat_none_as_null = true)]
+pub struct ArchivedContent {
+    pub kind: String,
+    pub uid: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub title: String,
+    pub body: String,
+    pub published: bool,
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub deleted_at: Option<i64>,
+    #[serde(default = "default_revision")]
+    pub revision: i32,
+    #[serde(default = "default_visibility")]
+    pub visibility: String,
+    #[serde(default)]
+    pub password_hash: Option<String>,
+    #[serde(default)]
+    pub nav_order: Option<i32>,
+    #[serde(default)]
+    pub nav_title: Option<String>,
+    #[serde(default)]
+    pub toc: Option<String>,
+}
+
+fn default_visibility() -> String {
+    crate::models::Visibility::Public.into()
+}
+
+fn default_revision() -> i32 {
+    1
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ImportMode {
+    /// Keep existing data, contents from the archive overwrite the ones with the same uid
+    Merge,
+    /// Wipe every content and tag before importing
+    Replace,
+    /// Validate and report without writing anything
+    DryRun,
+}
+
+#[derive(Serialize, Default, Debug)]
+pub struct ExportReport {
+    pub contents: usize,
+    pub tags: usize,
+    pub links: usize,
+}
+
+#[derive(Serialize, Default, Debug)]
+pub struct ArchiveReport {
+    pub created: usize,
+    pub updated: usize,
+    pub tags: usize,
+    pub links: usize,
+}
+
+pub fn export(path: impl AsRef<Path>) -> Result<ExportReport> {
+    let conn = &mut POOL.clone().get()?;
+
+    let (contents, tags, links) = conn.transaction::<_, Error, _>(|conn| {
+        let contents = contents::table
+            .order(contents::id.asc())
+            .select(ArchivedContent::as_select())
+            .load(conn)?;
+        let tags = tags::table
+            .order(tags::id.asc())
+            .select(tags::tag)
+            .load::<String>(conn)?;
+        let links = contents_tags::table
+            .inner_join(contents::table)
+            .inner_join(tags::table)
+            .select((contents::kind, contents::uid, tags::tag))
+            .load::<(String, String, String)>(conn)?;
+        Ok((contents, tags, links))
+    })?;
+
+    let report = ExportReport {
+        contents: contents.len(),
+        tags: tags.len(),
+        links: links.len(),
+    };
+
+    let records = std::iter::once(Record::Header {
+        format: ARCHIVE_FORMAT.into(),
+        version: ARCHIVE_VERSION,
+        exported_at: crate::utils::now(),
+    })
+    .chain(contents.into_iter().map(Record::Content))
+    .chain(tags.into_iter().map(|tag| Record::Tag { tag }))
+    .chain(
+        links
+            .into_iter()
+            .map(|(kind, uid, tag)| Record::ContentTag { kind, uid, tag }),
+    );
+
+    let mut writer = BufWriter::new(File::create(path)?);
+    for record in records {
+        serde_json::to_writer(&mut writer, &record)?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()?;
+
+    Ok(report)
+}
+
+pub fn import(path: impl AsRef<Path>, mode: ImportMode) -> Result<ArchiveReport> {
+    let archive = Archive::read(path)?;
+    let conn = &mut POOL.clone().get()?;
+
+    let (report, recorded) = conn.transaction::<_, Error, _>(|conn| {
+        // What was there before a replace wipes it, to tell subscribers what changed
+        let previous: HashSet<(String, String)> = contents::table
+            .select((contents::kind, contents::uid))
+            .load::<(String, String)>(conn)?
+            .into_iter()
+            .collect();
+
+        if mode == ImportMode::Replace {
+            diesel::delete(contents_tags::table).execute(conn)?;
+            diesel::delete(contents::table).execute(conn)?;
+            diesel::delete(tags::table).execute(conn)?;
+        }
+
+        let mut report = ArchiveReport {
+            tags: archive.tags.len(),
+            links: archive.links.len(),
+            ..Default::default()
+        };
+
+        let existing: HashSet<(String, String)> = contents::table
+            .select((contents::kind, contents::uid))
+            .load::<(String, String)>(conn)?
+            .into_iter()
+            .collect();
+
+        for content in &archive.contents {
+            let is_existing = existing.contains(&(content.kind.clone(), content.uid.clone()));
+            if is_existing {
+                report.updated += 1;
+            } else {
+                report.created += 1;
+            }
+            if mode == ImportMode::DryRun {
+                continue;
+            }
+
+            if is_existing {
+                let id = diesel::update(
+                    contents::table
+                        .filter(contents::kind.eq(&content.kind))
+                        .filter(contents::uid.eq(&content.uid)),
+                )
+                .set(content)
+                .returning(contents::id)
+                .get_result::<i32>(conn)?;
+                diesel::delete(contents_tags::table.filter(contents_tags::content_id.eq(id)))
+                    .execute(conn)?;
+            } else {
+                diesel::insert_into(contents::table)
+                    .values(content)
+                    .execute(conn)?;
+            }
+        }
+
+        if mode == ImportMode::DryRun {
+            return Ok((report, vec![]));
+        }
+
+        for tag in &archive.tags {
+            diesel::insert_into(tags::table)
+                .values(NewTag::from(tag))
+                .on_conflict_do_nothing()
+                .execute(conn)?;
+        }
+
+        for (kind, uid, tag) in &archive.links {
+            let content_id = contents::table
+                .filter(contents::kind.eq(kind))
+                .filter(contents::uid.eq(uid))
+                .select(contents::id)
+                .get_result::<i32>(conn)?;
+            let tag_id = tags::table
+                .filter(tags::tag.eq(tag))
+                .select(tags::id)
+                .get_result::<i32>(conn)?;
+            diesel::insert_into(contents_tags::table)
+                .values((
+                    contents_tags::content_id.eq(content_id),
+                    contents_tags::tag_id.eq(tag_id),
+                ))
+                .on_conflict_do_nothing()
+                .execute(conn)?;
+        }
+
+        let imported: HashSet<(&str, &str)> = archive
+            .contents
+            .iter()
+            .map(|x| (x.kind.as_str(), x.uid.as_str()))
+            .collect();
+        let (updated, created): (Vec<_>, Vec<_>) = imported
+            .iter()
+            .partition(|(kind, uid)| previous.contains(&(kind.to_string(), uid.to_string())));
+        let mut recorded = events::record_contents(conn, EventKind::Created, created)?;
+        recorded.extend(events::record_contents(conn, EventKind::Updated, updated)?);
+        if mode == ImportMode::Replace {
+            let deleted = previous
+                .iter()
+                .map(|(kind, uid)| (kind.as_str(), uid.as_str()))
+                .filter(|key| !imported.contains(key));
+            recorded.extend(events::record_contents(conn, EventKind::Deleted, deleted)?);
+        }
+
+        Ok((report, recorded))
+    })?;
+    events::broadcast(recorded);
+
+    Ok(report)
+}
+
+/// A fully parsed and validated archive, nothing is written before this exists.
+struct Archive {
+    contents: Vec<ArchivedContent>,
+    tags: Vec<String>,
+    links: Vec<(String, String, String)>,
+}
+
+impl Archive {
+    fn read(path: impl AsRef<Path>) -> Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut lines = reader.lines().enumerate();
+
+        match lines.next() {
+            Some((_, line)) => match serde_json::from_str::<Record>(&line?) {
+                Ok(Record::Header {
+                    format, version, ..
+                }) if format == ARCHIVE_FORMAT && version <= ARCHIVE_VERSION => {}
+                Ok(Record::Header { format, .. }) if format != ARCHIVE_FORMAT => {
+                    return Err(Error::ArchiveError(format!(
+                        "Not an aftershock archive, the format is {format}"
+                    )));
+                }
+                Ok(Record::Header { version, .. }) => {
+                    return Err(Error::ArchiveError(format!(
+                        "Unsupported archive version {version}"
+                    )));
+                }
+                _ => {
+                    return Err(Error::ArchiveError(
+                        "Missing archive header on line 1".into(),
+                    ));
+                }
+            },
+            None => return Err(Error::ArchiveError("Empty archive".into())),
+        }
+
+        let mut archive = Archive {
+            contents: vec![],
+            tags: vec![],
+            links: vec![],
+        };
+        let mut content_keys = HashSet::new();
+        let mut tag_set = HashSet::new();
+
+        for (index, line) in lines {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let lineno = index + 1;
+            let invalid = |msg: String| Error::ArchiveError(format!("Line {lineno}: {msg}"));
+
+            match serde_json::from_str::<Record>(&line).map_err(|e| invalid(e.to_string()))? {
+                Record::Header { .. } => return Err(invalid("Unexpected header".into())),
+                Record::Content(content) => {
+                    ContentKind::try_from(content.kind.as_str())
+                        .map_err(|_| invalid(format!("Unknown kind {}", content.kind)))?;
+                    Visibility::try_from(content.visibility.as_str()).map_err(|_| {
+                        invalid(format!("Unknown visibility {}", content.visibility))
+                    })?;
+                    if content.uid.is_empty() {
+                        return Err(invalid("Empty uid".into()));
+                    }
+                    if !content_keys.insert((content.kind.clone(), content.uid.clone())) {
+                        return Err(invalid(format!("Duplicated uid {}", content.uid)));
+                    }
+                    archive.contents.push(content);
+                }
+                Record::Tag { tag } => {
+                    if tag_set.insert(tag.clone()) {
+                        archive.tags.push(tag);
+                    }
+                }
+                Record::ContentTag { kind, uid, tag } => {
+                    archive.links.push((kind, uid, tag));
+                }
+            }
+        }
+
+        // Links may appear before what they point at, so check them at the end
+        for (kind, uid, tag) in &archive.links {
+            if !content_keys.contains(&(kind.clone(), uid.clone())) {
+                return Err(Error::ArchiveError(format!(
+                    "Tag link points at unknown {kind} {uid}"
+                )));
+            }
+            if !tag_set.contains(tag) {
+                return Err(Error::ArchiveError(format!(
+                    "Tag link points at unknown tag {tag}"
+                )));
+            }
+        }
+
+        Ok(archive)
+    }
+}
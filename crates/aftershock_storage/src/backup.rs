@@ -0,0 +1,229 @@
+//! Online snapshots of the database.
+//!
+//! Snapshots are taken with `VACUUM INTO` on a connection of their own, which produces a
+//! consistent copy while the pooled connection keeps serving requests. Reads go on as
+//! usual, writes wait for the snapshot to finish. Each snapshot is reopened and checked
+//! with `PRAGMA integrity_check` before older ones are rotated away. Runs never overlap.
+
+use std::{
+    collections::HashSet,
+    env,
+    path::{Path, PathBuf},
+    sync::{LazyLock, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use diesel::{prelude::*, sql_types::Text};
+use serde::Serialize;
+
+use crate::{Result, error::Error, pool::establish_dedicated};
+
+const SNAPSHOT_PREFIX: &str = "aftershock-";
+const SNAPSHOT_SUFFIX: &str = ".db";
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+static LAST_STATUS: LazyLock<Mutex<Option<BackupStatus>>> = LazyLock::new(|| Mutex::new(None));
+/// Held for the whole of a run, so a backup on demand waits for a scheduled one
+static RUNNING: Mutex<()> = Mutex::new(());
+
+#[derive(Clone, Debug)]
+pub struct BackupConfig {
+    pub dir: PathBuf,
+    /// `None` disables scheduled backups, on-demand backups still work
+    pub interval: Option<Duration>,
+    pub keep_daily: usize,
+    pub keep_weekly: usize,
+}
+
+impl BackupConfig {
+    /// Read the configuration from `AFTERSHOCK_BACKUP_*` environment variables.
+    pub fn from_env() -> Self {
+        fn var<T: std::str::FromStr>(key: &str) -> Option<T> {
+            env::var(key).ok().and_then(|x| x.parse().ok())
+        }
+
+        Self {
+            dir: env::var("AFTERSHOCK_BACKUP_DIR")
+                .unwrap_or_else(|_| "./db/backups".into())
+                .into(),
+            interval: var::<u64>("AFTERSHOCK_BACKUP_INTERVAL")
+                .filter(|x| *x > 0)
+                .map(Duration::from_secs),
+            keep_daily: var("AFTERSHOCK_BACKUP_KEEP_DAILY").unwrap_or(7),
+            keep_weekly: var("AFTERSHOCK_BACKUP_KEEP_WEEKLY").unwrap_or(4),
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct BackupStatus {
+    pub file: String,
+    pub started_at: i64,
+    pub finished_at: i64,
+    pub size: u64,
+    pub verified: bool,
+    pub error: Option<String>,
+    pub removed: Vec<String>,
+}
+
+#[derive(QueryableByName)]
+struct IntegrityCheck {
+    #[diesel(sql_type = Text)]
+    integrity_check: String,
+}
+
+pub fn last_status() -> Option<BackupStatus> {
+    LAST_STATUS.lock().unwrap().clone()
+}
+
+/// Take a snapshot, verify it and rotate the old ones. The outcome is kept for
+/// [`last_status`] whether it succeeded or not.
+pub fn run(config: &BackupConfig) -> Result<BackupStatus> {
+    let _running = RUNNING.lock().unwrap_or_else(|e| e.into_inner());
+
+    // Milliseconds keep two runs within the same second apart
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let started_at = now.as_secs() as i64;
+    let file = config.dir.join(format!(
+        "{SNAPSHOT_PREFIX}{started_at}-{:03}{SNAPSHOT_SUFFIX}",
+        now.subsec_millis()
+    ));
+    // Whatever is at `file` after a failure is ours to clean up, unless it was there before
+    let existed = file.exists();
+
+    let outcome = if existed {
+        Err(Error::BackupError(format!(
+            "{} already exists",
+            file.display()
+        )))
+    } else {
+        snapshot(&file)
+    }
+    .and_then(|size| {
+        verify(&file)?;
+        let removed = rotate(&config.dir, config.keep_daily, config.keep_weekly)?;
+        Ok((size, removed))
+    });
+
+    let mut status = BackupStatus {
+        file: file.to_string_lossy().into_owned(),
+        started_at,
+        finished_at: crate::utils::now(),
+        size: 0,
+        verified: false,
+        error: None,
+        removed: vec![],
+    };
+    let ret = match outcome {
+        Ok((size, removed)) => {
+            status.size = size;
+            status.verified = true;
+            status.removed = removed
+                .iter()
+                .map(|x| x.to_string_lossy().into_owned())
+                .collect();
+            Ok(status.clone())
+        }
+        Err(e) => {
+            // Never leave a snapshot around that we could not verify
+            if !existed {
+                let _ = std::fs::remove_file(&file);
+            }
+            status.error = Some(e.to_string());
+            Err(Error::BackupError(e.to_string()))
+        }
+    };
+
+    *LAST_STATUS.lock().unwrap() = Some(status);
+    ret
+}
+
+/// Run [`run`] forever at the configured interval.
+pub async fn schedule(config: BackupConfig) {
+    let Some(interval) = config.interval else {
+        return;
+    };
+    let mut ticker = tokio::time::interval(interval);
+    // The first tick completes immediately, skip it so startup stays quick
+    ticker.tick().await;
+
+    loop {
+        ticker.tick().await;
+        let config = config.clone();
+        match tokio::task::spawn_blocking(move || run(&config)).await {
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) => eprintln!("Failed to back up the database: {e}"),
+            Err(e) => eprintln!("Backup did not finish: {e}"),
+        }
+    }
+}
+
+fn snapshot(file: &Path) -> Result<u64> {
+    if let Some(dir) = file.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+
+    let conn = &mut establish_dedicated().map_err(|e| Error::BackupError(e.to_string()))?;
+    let target = file.to_string_lossy().replace('\'', "''");
+    diesel::sql_query(format!("VACUUM INTO '{target}'")).execute(conn)?;
+
+    Ok(std::fs::metadata(file)?.len())
+}
+
+fn verify(file: &Path) -> Result<()> {
+    let mut conn = SqliteConnection::establish(&file.to_string_lossy())
+        .map_err(|e| Error::BackupError(e.to_string()))?;
+    let result = diesel::sql_query("PRAGMA integrity_check").load::<IntegrityCheck>(&mut conn)?;
+
+    match result.as_slice() {
+        [row] if row.integrity_check == "ok" => Ok(()),
+        rows => Err(Error::BackupError(
+            rows.iter()
+                .map(|x| x.integrity_check.as_str())
+                .collect::<Vec<_>>()
+                .join("; "),
+        )),
+    }
+}
+
+/// Keep the newest snapshot of each of the last `keep_daily` days and `keep_weekly`
+/// weeks (UTC), plus the newest one overall. Everything else is removed.
+fn rotate(dir: &Path, keep_daily: usize, keep_weekly: usize) -> Result<Vec<PathBuf>> {
+    let mut snapshots = std::fs::read_dir(dir)?
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let stamp = path
+                .file_name()?
+                .to_str()?
+                .strip_prefix(SNAPSHOT_PREFIX)?
+                .strip_suffix(SNAPSHOT_SUFFIX)?;
+            // `<seconds>-<milliseconds>`, or only seconds for older snapshots
+            let (seconds, millis) = stamp.split_once('-').unwrap_or((stamp, "0"));
+            let timestamp = (seconds.parse::<i64>().ok()?, millis.parse::<u32>().ok()?);
+            Some((timestamp, path))
+        })
+        .collect::<Vec<_>>();
+    snapshots.sort_unstable_by_key(|x| std::cmp::Reverse(x.0));
+
+    let mut days = HashSet::new();
+    let mut weeks = HashSet::new();
+    let mut removed = vec![];
+    for (index, ((timestamp, _), path)) in snapshots.into_iter().enumerate() {
+        let day = timestamp.div_euclid(SECONDS_PER_DAY);
+        // 1970-01-01 is a Thursday, shift so weeks start on Monday
+        let week = (day + 3).div_euclid(7);
+
+        let keep_day = days.len() < keep_daily && days.insert(day);
+        let keep_week = weeks.len() < keep_weekly && weeks.insert(week);
+        if index == 0 || keep_day || keep_week {
+            continue;
+        }
+
+        std::fs::remove_file(&path)?;
+        removed.push(path);
+    }
+
+    Ok(removed)
+}
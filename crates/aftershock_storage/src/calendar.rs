@@ -0,0 +1,75 @@
+//! Years and months in a timezone, turned into `created_at` ranges and back.
+
+use std::collections::BTreeMap;
+
+use aftershock_bridge::ArchiveMonth;
+use chrono::{Datelike, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+
+use crate::{Result, error::Error};
+
+pub fn parse_tz(name: Option<&str>) -> Result<Tz> {
+    match name {
+        None => Ok(Tz::UTC),
+        Some(name) => name
+            .parse()
+            .map_err(|_| Error::BadRequest(format!("Unknown timezone {name}"))),
+    }
+}
+
+/// Unix timestamp of local midnight starting `date`. When DST skips midnight, the
+/// first instant of the day is used instead.
+fn start_of_day(tz: Tz, date: NaiveDate) -> i64 {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap_or_default();
+    match tz.from_local_datetime(&midnight).earliest() {
+        Some(time) => time.timestamp(),
+        None => tz
+            .from_local_datetime(&(midnight + chrono::Duration::hours(1)))
+            .earliest()
+            .map(|x| x.timestamp())
+            .unwrap_or_else(|| midnight.and_utc().timestamp()),
+    }
+}
+
+/// `[start, end)` of a whole year, or of one of its months, in `tz`.
+pub fn period(tz: Tz, year: i32, month: Option<u32>) -> Result<(i64, i64)> {
+    let invalid = || Error::BadRequest(format!("Invalid period {year}/{month:?}"));
+    // Both come from the path, `i32::MAX` or `u32::MAX` must not overflow
+    let next_year = || {
+        year.checked_add(1)
+            .and_then(|year| NaiveDate::from_ymd_opt(year, 1, 1))
+    };
+    let (first, next) = match month {
+        None => (NaiveDate::from_ymd_opt(year, 1, 1), next_year()),
+        Some(12) => (NaiveDate::from_ymd_opt(year, 12, 1), next_year()),
+        Some(month) => (
+            NaiveDate::from_ymd_opt(year, month, 1),
+            month
+                .checked_add(1)
+                .and_then(|month| NaiveDate::from_ymd_opt(year, month, 1)),
+        ),
+    };
+
+    Ok((
+        start_of_day(tz, first.ok_or_else(invalid)?),
+        start_of_day(tz, next.ok_or_else(invalid)?),
+    ))
+}
+
+/// Count `timestamps` per month of `tz`, newest month first.
+pub fn month_counts(timestamps: Vec<i64>, tz: Tz) -> Vec<ArchiveMonth> {
+    let mut counts: BTreeMap<(i32, u32), i64> = BTreeMap::new();
+    for timestamp in timestamps {
+        let Some(time) = Utc.timestamp_opt(timestamp, 0).single() else {
+            continue;
+        };
+        let time = time.with_timezone(&tz);
+        *counts.entry((time.year(), time.month())).or_default() += 1;
+    }
+
+    counts
+        .into_iter()
+        .rev()
+        .map(|((year, month), count)| ArchiveMonth { year, month, count })
+        .collect()
+}
@@ -0,0 +1,50 @@
+use clap::{Parser, Subcommand, ValueEnum};
+
+use crate::archive::ImportMode;
+
+#[derive(Parser)]
+#[command(version, about)]
+#[command(propagate_version = true)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Commands>,
+}
+
+#[derive(Subcommand)]
+pub enum Commands {
+    /// Serve the storage API, the default when no command is given
+    Serve,
+    /// Export every content, tag and tag link into an archive
+    Export {
+        /// The path to the archive file
+        file: String,
+    },
+    /// Import an archive exported by `export`
+    Import {
+        /// The path to the archive file
+        file: String,
+        /// How the archive is applied to the existing data
+        #[arg(long, value_enum, default_value_t = ImportModeArg::Merge)]
+        mode: ImportModeArg,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+pub enum ImportModeArg {
+    /// Keep existing data, archived contents overwrite the ones with the same uid
+    Merge,
+    /// Remove every content and tag before importing
+    Replace,
+    /// Validate the archive and report what would change
+    DryRun,
+}
+
+impl From<ImportModeArg> for ImportMode {
+    fn from(value: ImportModeArg) -> Self {
+        match value {
+            ImportModeArg::Merge => ImportMode::Merge,
+            ImportModeArg::Replace => ImportMode::Replace,
+            ImportModeArg::DryRun => ImportMode::DryRun,
+        }
+    }
+}
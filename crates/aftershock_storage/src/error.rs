@@ -1,4 +1,8 @@
-use axum::{http::StatusCode, response::IntoResponse};
+use axum::{
+    Json,
+    http::{StatusCode, header::ETAG},
+    response::IntoResponse,
+};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -9,20 +13,46 @@ pub enum Error {
     DatabaseError(#[from] diesel::result::Error),
     #[error("Database Migration Error: {0}")]
     MigrationError(#[from] Box<dyn core::error::Error + Send + Sync>),
+    #[error("IO Error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Serialization Error: {0}")]
+    SerializationError(#[from] serde_json::Error),
 
     #[error("Not Found: {0}")]
     NotFound(String),
+    #[error("Bad Request: {0}")]
+    BadRequest(String),
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+    #[error("Precondition Failed: the content is now at revision {}", .0.revision)]
+    PreconditionFailed(Box<aftershock_bridge::Post>),
 
     #[error("Wrong content kind literal")]
     ContentKindError,
+
+    #[error("Invalid archive: {0}")]
+    ArchiveError(String),
+
+    #[error("Backup Error: {0}")]
+    BackupError(String),
 }
 
 impl IntoResponse for Error {
     fn into_response(self) -> axum::response::Response {
         let msg = format!("{self}");
 
-        match &self {
+        match self {
             Self::NotFound(_) => (StatusCode::NOT_FOUND, msg).into_response(),
+            Self::BadRequest(_) | Self::ContentKindError => {
+                (StatusCode::BAD_REQUEST, msg).into_response()
+            }
+            Self::Forbidden(_) => (StatusCode::FORBIDDEN, msg).into_response(),
+            Self::PreconditionFailed(post) => (
+                StatusCode::PRECONDITION_FAILED,
+                [(ETAG, crate::routes::api::etag(&post))],
+                Json(*post),
+            )
+                .into_response(),
             Self::DatabaseError(diesel::result::Error::NotFound) => {
                 (StatusCode::NOT_FOUND, "Resource not found".to_string()).into_response()
             }
@@ -0,0 +1,123 @@
+use std::sync::LazyLock;
+
+use aftershock_bridge::ContentEvent;
+use diesel::prelude::*;
+use tokio::sync::broadcast;
+
+use crate::{
+    Result,
+    models::{Event, NewEvent},
+    schema::events,
+};
+
+/// How many events are kept in the database for `Last-Event-ID` resume.
+const EVENT_LOG_SIZE: i32 = 1024;
+const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+static CHANNEL: LazyLock<broadcast::Sender<ContentEvent>> =
+    LazyLock::new(|| broadcast::channel(EVENT_CHANNEL_CAPACITY).0);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EventKind {
+    Created,
+    Updated,
+    Deleted,
+    Published,
+    Restored,
+}
+
+impl EventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventKind::Created => "created",
+            EventKind::Updated => "updated",
+            EventKind::Deleted => "deleted",
+            EventKind::Published => "published",
+            EventKind::Restored => "restored",
+        }
+    }
+}
+
+/// Persist one event per affected content, returning them for [`broadcast`].
+///
+/// Inside a transaction, broadcast only after the outermost one has committed: a rolled
+/// back event never happened, and SQLite hands its id to the next one.
+pub fn record(
+    conn: &mut SqliteConnection,
+    event: EventKind,
+    posts: &[aftershock_bridge::Post],
+) -> Result<Vec<ContentEvent>> {
+    record_contents(
+        conn,
+        event,
+        posts
+            .iter()
+            .map(|post| (post.kind.as_str(), post.uid.as_str())),
+    )
+}
+
+/// [`record`] for contents known by `(kind, uid)` alone.
+pub fn record_contents<'a>(
+    conn: &mut SqliteConnection,
+    event: EventKind,
+    contents: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> Result<Vec<ContentEvent>> {
+    let now = crate::utils::now();
+    let new_events: Vec<NewEvent> = contents
+        .into_iter()
+        .map(|(kind, uid)| NewEvent {
+            event: event.as_str(),
+            uid,
+            kind,
+            created_at: now,
+        })
+        .collect();
+    if new_events.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let recorded = conn.transaction::<_, crate::error::Error, _>(|conn| {
+        let recorded = new_events
+            .iter()
+            .map(|new_event| {
+                diesel::insert_into(events::table)
+                    .values(new_event)
+                    .returning(Event::as_returning())
+                    .get_result(conn)
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        // Keep the log small, only the tail is needed to resume a stream
+        if let Some(last) = recorded.last() {
+            diesel::delete(events::table.filter(events::id.le(last.id - EVENT_LOG_SIZE)))
+                .execute(conn)?;
+        }
+
+        Ok(recorded)
+    })?;
+
+    Ok(recorded.into_iter().map(|x| x.into()).collect())
+}
+
+/// Fan `events` out to every subscriber.
+pub fn broadcast(events: Vec<ContentEvent>) {
+    for event in events {
+        // Sending only fails when nobody is listening, which is fine
+        let _ = CHANNEL.send(event);
+    }
+}
+
+pub fn subscribe() -> broadcast::Receiver<ContentEvent> {
+    CHANNEL.subscribe()
+}
+
+/// Events recorded after `last_id`, oldest first.
+pub fn since(conn: &mut SqliteConnection, last_id: i32) -> Result<Vec<ContentEvent>> {
+    let ret = events::table
+        .filter(events::id.gt(last_id))
+        .order(events::id.asc())
+        .select(Event::as_select())
+        .load(conn)?;
+
+    Ok(ret.into_iter().map(|x| x.into()).collect())
+}
@@ -2,12 +2,20 @@ use axum::Router;
 use pool::{DbPool, get_connection_pool};
 use std::sync::LazyLock;
 
+pub mod archive;
+pub mod backup;
+mod calendar;
+pub mod command;
 pub mod error;
+pub mod events;
 pub mod migration;
 mod models;
 mod pool;
+pub mod preview;
 pub mod routes;
 mod schema;
+mod search;
+pub mod trash;
 mod utils;
 
 type Result<T> = std::result::Result<T, error::Error>;
@@ -15,7 +23,7 @@ type Result<T> = std::result::Result<T, error::Error>;
 static POOL: LazyLock<DbPool> = LazyLock::new(get_connection_pool);
 
 pub fn create_router() -> Router {
-    use axum::routing::get;
+    use axum::routing::{delete, get, post};
 
     Router::new()
         .route(
@@ -23,7 +31,26 @@ pub fn create_router() -> Router {
             get(routes::api::get_published_posts).post(routes::api::create_content),
         )
         .route("/api/v1/posts/all", get(routes::api::get_all_posts))
+        .route("/api/v1/posts/search", get(routes::api::search_posts))
+        .route("/api/v1/posts/tags", get(routes::api::get_post_tag_counts))
+        .route(
+            "/api/v1/posts/uid/{post_uid}/neighbors",
+            get(routes::api::get_post_neighbors),
+        )
+        .route("/api/v1/posts/archive", get(routes::api::get_post_archive))
+        .route(
+            "/api/v1/posts/archive/{year}",
+            get(routes::api::get_posts_meta_by_year),
+        )
+        .route(
+            "/api/v1/posts/archive/{year}/{month}",
+            get(routes::api::get_posts_meta_by_month),
+        )
         .route("/api/v1/posts/meta", get(routes::api::get_published_posts_meta))
+        .route(
+            "/api/v1/posts/meta/page/{page}",
+            get(routes::api::get_published_posts_meta_page),
+        )
         .route("/api/v1/posts/all-meta", get(routes::api::get_all_posts_meta))
         .route(
             "/api/v1/posts/uid/{post_uid}",
@@ -31,12 +58,33 @@ pub fn create_router() -> Router {
                 .put(routes::api::update_post_by_uid)
                 .delete(routes::api::delete_post_by_uid),
         )
+        .route(
+            "/api/v1/posts/uid/{post_uid}/unlock",
+            post(routes::api::unlock_post_by_uid),
+        )
+        .route(
+            "/api/v1/posts/uid/{post_uid}/preview",
+            post(routes::api::create_post_preview),
+        )
+        .route("/api/v1/posts/trash", get(routes::api::get_trashed_posts_meta))
+        .route(
+            "/api/v1/posts/trash/{post_uid}",
+            delete(routes::api::purge_post_by_uid),
+        )
+        .route(
+            "/api/v1/posts/trash/{post_uid}/restore",
+            post(routes::api::restore_post_by_uid),
+        )
         .route("/api/v1/posts/tag/{tag}", get(routes::api::get_published_posts_by_tag))
         .route("/api/v1/posts/tag/{tag}/all", get(routes::api::get_all_posts_by_tag))
         .route(
             "/api/v1/posts/tag/{tag}/meta",
             get(routes::api::get_published_posts_meta_by_tag),
         )
+        .route(
+            "/api/v1/posts/tag/{tag}/meta/page/{page}",
+            get(routes::api::get_published_posts_meta_by_tag_page),
+        )
         .route(
             "/api/v1/posts/tag/{tag}/all-meta",
             get(routes::api::get_all_posts_meta_by_tag),
@@ -54,6 +102,23 @@ pub fn create_router() -> Router {
                 .put(routes::api::update_page_by_uid)
                 .delete(routes::api::delete_page_by_uid),
         )
+        .route(
+            "/api/v1/pages/uid/{post_uid}/unlock",
+            post(routes::api::unlock_page_by_uid),
+        )
+        .route(
+            "/api/v1/pages/uid/{post_uid}/preview",
+            post(routes::api::create_page_preview),
+        )
+        .route("/api/v1/pages/trash", get(routes::api::get_trashed_pages_meta))
+        .route(
+            "/api/v1/pages/trash/{post_uid}",
+            delete(routes::api::purge_page_by_uid),
+        )
+        .route(
+            "/api/v1/pages/trash/{post_uid}/restore",
+            post(routes::api::restore_page_by_uid),
+        )
         .route("/api/v1/pages/tag/{tag}", get(routes::api::get_published_pages_by_tag))
         .route("/api/v1/pages/tag/{tag}/all", get(routes::api::get_all_pages_by_tag))
         .route(
@@ -64,4 +129,10 @@ pub fn create_router() -> Router {
             "/api/v1/pages/tag/{tag}/all-meta",
             get(routes::api::get_all_pages_meta_by_tag),
         )
+        .route("/api/v1/preview/{token}", get(routes::api::get_preview))
+        .route("/api/v1/events", get(routes::api::stream_events))
+        .route(
+            "/api/v1/admin/backup",
+            get(routes::api::get_backup_status).post(routes::api::create_backup),
+        )
 }
@@ -1,17 +1,41 @@
 use std::env;
 
 use aftershock_storage::{
+    archive,
+    backup::{self, BackupConfig},
+    command::{Cli, Commands},
     create_router,
     migration::run_migrations,
+    trash::{self, TrashConfig},
 };
+use clap::Parser;
 use dotenvy::dotenv;
 
 #[tokio::main]
 async fn main() {
     dotenv().ok();
+    let cli = Cli::parse();
+    run_migrations().expect("Fail to run migrations");
+
+    match cli.command.unwrap_or(Commands::Serve) {
+        Commands::Serve => serve().await,
+        Commands::Export { file } => {
+            let report = archive::export(&file).expect("Fail to export");
+            println!("{}", serde_json::to_string_pretty(&report).unwrap());
+        }
+        Commands::Import { file, mode } => {
+            let report = archive::import(&file, mode.into()).expect("Fail to import");
+            println!("{}", serde_json::to_string_pretty(&report).unwrap());
+        }
+    }
+}
+
+async fn serve() {
     let port = env::var("AFTERSHOCK_DB_PORT").expect("AFTERSHOCK_DB_PORT is expected");
     let addr = format!("0.0.0.0:{port}");
-    run_migrations().expect("Fail to run migrations");
+
+    tokio::spawn(backup::schedule(BackupConfig::from_env()));
+    tokio::spawn(trash::schedule(TrashConfig::from_env()));
 
     let app = create_router();
 
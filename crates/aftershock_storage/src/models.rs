@@ -53,6 +53,63 @@ impl TryFrom<&str> for ContentKind {
     }
 }
 
+/// Who can see a content. `Private` is never stored, it is how unpublished contents are
+/// reported, the `visibility` column only keeps the audience used once published.
+#[derive(FromSqlRow, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub enum Visibility {
+    Public,
+    Unlisted,
+    Private,
+    Protected,
+}
+
+impl Visibility {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Visibility::Public => "public",
+            Visibility::Unlisted => "unlisted",
+            Visibility::Private => "private",
+            Visibility::Protected => "protected",
+        }
+    }
+}
+
+impl From<Visibility> for String {
+    fn from(value: Visibility) -> Self {
+        value.as_str().into()
+    }
+}
+
+impl<DB> FromSql<Text, DB> for Visibility
+where
+    DB: Backend,
+    String: FromSql<Text, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        let visibility = String::from_sql(bytes)?;
+        match Visibility::try_from(visibility.as_str()) {
+            Ok(visibility) => Ok(visibility),
+            Err(_) => Err(format!("Unrecognized visibility {visibility}").into()),
+        }
+    }
+}
+
+impl TryFrom<&str> for Visibility {
+    type Error = crate::error::Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "public" => Ok(Self::Public),
+            "unlisted" => Ok(Self::Unlisted),
+            "private" => Ok(Self::Private),
+            "protected" => Ok(Self::Protected),
+            _ => Err(crate::error::Error::BadRequest(format!(
+                "Unknown visibility {value}"
+            ))),
+        }
+    }
+}
+
 #[derive(Queryable, Selectable, Identifiable, PartialEq, Serialize, Deserialize, Debug)]
 #[diesel(table_name = crate::schema::contents, check_for_backend(diesel::sqlite::Sqlite))]
 pub struct Content {
@@ -65,12 +122,35 @@ pub struct Content {
     pub published: bool,
     pub uid: String,
     pub summary: Option<String>,
+    pub deleted_at: Option<i64>,
+    pub revision: i32,
+    pub visibility: Visibility,
+    #[serde(skip)]
+    pub password_hash: Option<String>,
+    pub nav_order: Option<i32>,
+    pub nav_title: Option<String>,
+    /// JSON array of [`aftershock_bridge::TocEntry`]
+    pub toc: Option<String>,
+}
+
+impl Content {
+    pub fn effective_visibility(&self) -> Visibility {
+        match self.published {
+            true => self.visibility,
+            false => Visibility::Private,
+        }
+    }
 }
 
 impl IntoPost for (Content, Vec<Tag>) {
     fn into_post(self) -> aftershock_bridge::Post {
         let (content, tags) = self;
         let tags = tags.into_iter().map(|tag| tag.into()).collect();
+        let visibility = content.effective_visibility().into();
+        let nav = content.nav_order.map(|order| aftershock_bridge::PageNav {
+            order,
+            title: content.nav_title,
+        });
         aftershock_bridge::Post {
             uid: content.uid,
             kind: content.kind.into(),
@@ -81,6 +161,13 @@ impl IntoPost for (Content, Vec<Tag>) {
             tags,
             summary: content.summary,
             published: content.published,
+            revision: content.revision,
+            visibility,
+            nav,
+            toc: content
+                .toc
+                .and_then(|toc| serde_json::from_str(&toc).ok())
+                .unwrap_or_default(),
         }
     }
 }
@@ -96,6 +183,19 @@ pub struct NewContent<'a> {
     pub published: bool,
     pub uid: String,
     pub summary: Option<String>,
+    pub visibility: String,
+    pub password_hash: Option<String>,
+    pub nav_order: Option<i32>,
+    pub nav_title: Option<String>,
+    pub toc: Option<String>,
+}
+
+/// Store a heading outline as JSON, leaving it out when there is none.
+fn serialize_toc(toc: &[aftershock_bridge::TocEntry]) -> crate::Result<Option<String>> {
+    match toc.is_empty() {
+        true => Ok(None),
+        false => Ok(Some(serde_json::to_string(toc)?)),
+    }
 }
 
 impl<'a> NewContent<'a> {
@@ -122,19 +222,68 @@ impl<'a> NewContent<'a> {
             published,
             uid,
             summary,
+            visibility: Visibility::Public.into(),
+            password_hash: None,
+            nav_order: None,
+            nav_title: None,
+            toc: None,
+        }
+    }
+
+    pub fn with_toc(self, toc: &[aftershock_bridge::TocEntry]) -> crate::Result<Self> {
+        Ok(Self {
+            toc: serialize_toc(toc)?,
+            ..self
+        })
+    }
+
+    pub fn with_nav(self, nav: Option<aftershock_bridge::PageNav>) -> Self {
+        let (nav_order, nav_title) = split_nav(nav);
+        Self {
+            nav_order,
+            nav_title,
+            ..self
+        }
+    }
+
+    pub fn with_visibility(
+        mut self,
+        visibility: Visibility,
+        password: Option<&str>,
+    ) -> crate::Result<Self> {
+        match visibility {
+            Visibility::Private => self.published = false,
+            Visibility::Protected if password.is_none() => {
+                return Err(crate::error::Error::BadRequest(
+                    "Protected contents need a password".into(),
+                ));
+            }
+            visibility => self.visibility = visibility.into(),
         }
+        self.password_hash = password.map(utils::hash_password).transpose()?;
+        Ok(self)
     }
 }
 
-impl<'a> From<&'a aftershock_bridge::NewPost> for NewContent<'a> {
-    fn from(value: &'a aftershock_bridge::NewPost) -> Self {
+impl<'a> TryFrom<&'a aftershock_bridge::NewPost> for NewContent<'a> {
+    type Error = crate::error::Error;
+
+    fn try_from(value: &'a aftershock_bridge::NewPost) -> Result<Self, Self::Error> {
+        let visibility = match value.visibility.as_deref() {
+            Some(visibility) => Visibility::try_from(visibility)?,
+            None => Visibility::Public,
+        };
+
         Self::new(
-            ContentKind::try_from(value.kind.as_str()).unwrap(),
+            ContentKind::try_from(value.kind.as_str())?,
             &value.title,
             &value.body,
             value.published,
             value.summary.clone(),
         )
+        .with_visibility(visibility, value.password.as_deref())?
+        .with_toc(&value.toc)
+        .map(|content| content.with_nav(value.nav.clone()))
     }
 }
 
@@ -151,17 +300,71 @@ pub struct UpdateContent {
     pub body: Option<String>,
     #[serde(default)]
     pub published: Option<bool>,
+    #[serde(default)]
+    pub visibility: Option<String>,
+    /// `Some(None)` forgets the password when leaving `protected`
+    #[serde(default)]
+    pub password_hash: Option<Option<String>>,
+    #[serde(default)]
+    pub nav_order: Option<Option<i32>>,
+    #[serde(default)]
+    pub nav_title: Option<Option<String>>,
+    #[serde(default)]
+    pub toc: Option<Option<String>>,
 }
 
-impl From<aftershock_bridge::UpdatePost> for UpdateContent {
-    fn from(value: aftershock_bridge::UpdatePost) -> Self {
-        Self {
+fn split_nav(nav: Option<aftershock_bridge::PageNav>) -> (Option<i32>, Option<String>) {
+    match nav {
+        Some(nav) => (Some(nav.order), nav.title),
+        None => (None, None),
+    }
+}
+
+impl TryFrom<aftershock_bridge::UpdatePost> for UpdateContent {
+    type Error = crate::error::Error;
+
+    fn try_from(value: aftershock_bridge::UpdatePost) -> Result<Self, Self::Error> {
+        // Private is the same as a draft, so choosing it unpublishes
+        let (published, visibility) = match value.visibility.as_deref() {
+            Some(visibility) => match Visibility::try_from(visibility)? {
+                Visibility::Private => (Some(false), None),
+                visibility => (value.published, Some(visibility.into())),
+            },
+            None => (value.published, None),
+        };
+
+        let (nav_order, nav_title) = match value.nav {
+            Some(nav) => {
+                let (order, title) = split_nav(nav);
+                (Some(order), Some(title))
+            }
+            None => (None, None),
+        };
+
+        let password_hash = match visibility.as_deref() {
+            // Whether a protected content without a new password already has one is up to
+            // the worker, which sees the stored hash
+            Some("protected") | None => value
+                .password
+                .as_deref()
+                .map(utils::hash_password)
+                .transpose()?
+                .map(Some),
+            Some(_) => Some(None),
+        };
+
+        Ok(Self {
             created_at: None,
             updated_at: None,
             title: value.title,
             body: value.body,
-            published: value.published,
-        }
+            published,
+            visibility,
+            password_hash,
+            nav_order,
+            nav_title,
+            toc: value.toc.as_deref().map(serialize_toc).transpose()?,
+        })
     }
 }
 
@@ -208,3 +411,34 @@ impl From<(i32, i32)> for ContentTag {
         Self { content_id, tag_id }
     }
 }
+
+#[derive(Queryable, Selectable, Identifiable, PartialEq, Debug)]
+#[diesel(table_name = crate::schema::events, check_for_backend(diesel::sqlite::Sqlite))]
+pub struct Event {
+    pub id: i32,
+    pub event: String,
+    pub uid: String,
+    pub kind: String,
+    pub created_at: i64,
+}
+
+impl From<Event> for aftershock_bridge::ContentEvent {
+    fn from(value: Event) -> Self {
+        Self {
+            id: value.id,
+            event: value.event,
+            uid: value.uid,
+            kind: value.kind,
+            timestamp: value.created_at,
+        }
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::events, check_for_backend(diesel::sqlite::Sqlite))]
+pub struct NewEvent<'a> {
+    pub event: &'a str,
+    pub uid: &'a str,
+    pub kind: &'a str,
+    pub created_at: i64,
+}
@@ -1,19 +1,48 @@
 use diesel::{
+    connection::SimpleConnection,
     prelude::*,
-    r2d2::{ConnectionManager, Pool},
+    r2d2::{ConnectionManager, CustomizeConnection, Pool, PooledConnection},
 };
 use dotenvy::dotenv;
 use std::env;
 
 pub type DbPool = Pool<ConnectionManager<SqliteConnection>>;
+pub type DbConnection = PooledConnection<ConnectionManager<SqliteConnection>>;
 
-pub fn get_connection_pool() -> Pool<ConnectionManager<SqliteConnection>> {
+/// How long a connection waits for a lock held by another one, such as a backup reading
+/// the database, before giving up
+const BUSY_TIMEOUT_MS: u32 = 30_000;
+
+#[derive(Debug)]
+struct BusyTimeout;
+
+impl CustomizeConnection<SqliteConnection, diesel::r2d2::Error> for BusyTimeout {
+    fn on_acquire(&self, conn: &mut SqliteConnection) -> Result<(), diesel::r2d2::Error> {
+        set_busy_timeout(conn).map_err(diesel::r2d2::Error::QueryError)
+    }
+}
+
+fn set_busy_timeout(conn: &mut SqliteConnection) -> QueryResult<()> {
+    conn.batch_execute(&format!("PRAGMA busy_timeout = {BUSY_TIMEOUT_MS};"))
+}
+
+fn database_url() -> String {
     dotenv().ok();
+    env::var("DATABASE_URL").expect("DATABASE_URL is expected.")
+}
 
-    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL is expected.");
-    let manager = ConnectionManager::<SqliteConnection>::new(database_url);
+pub fn get_connection_pool() -> Pool<ConnectionManager<SqliteConnection>> {
+    let manager = ConnectionManager::<SqliteConnection>::new(database_url());
     Pool::builder()
         .max_size(1)
+        .connection_customizer(Box::new(BusyTimeout))
         .build(manager)
         .expect("Could not build connection pool")
 }
+
+/// A connection of its own, outside the pool, for work too long to hold the pooled one.
+pub fn establish_dedicated() -> ConnectionResult<SqliteConnection> {
+    let mut conn = SqliteConnection::establish(&database_url())?;
+    set_busy_timeout(&mut conn).map_err(|e| ConnectionError::BadConnection(e.to_string()))?;
+    Ok(conn)
+}
@@ -0,0 +1,103 @@
+//! Signed links to contents that are not published yet.
+//!
+//! A token is `base64(kind:uid:expires_at).base64(hmac)`, so nothing has to be stored to
+//! check it. The key comes from `AFTERSHOCK_PREVIEW_SECRET`, without it a random key is
+//! drawn at startup and every token dies with the process.
+
+use std::{env, sync::LazyLock, time::Duration};
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::{Result, error::Error, models::ContentKind};
+
+static CONFIG: LazyLock<PreviewConfig> = LazyLock::new(PreviewConfig::from_env);
+
+/// The longest a token may live, so its expiry can't overflow.
+pub const MAX_TTL: Duration = Duration::from_secs(365 * 24 * 60 * 60);
+
+#[derive(Clone, Debug)]
+pub struct PreviewConfig {
+    pub secret: Vec<u8>,
+    /// How long a token lives when the request does not say
+    pub ttl: Duration,
+}
+
+impl PreviewConfig {
+    /// Read `AFTERSHOCK_PREVIEW_SECRET` and `AFTERSHOCK_PREVIEW_TTL` (seconds, one day by default).
+    pub fn from_env() -> Self {
+        let secret = match env::var("AFTERSHOCK_PREVIEW_SECRET") {
+            Ok(secret) if !secret.is_empty() => secret.into_bytes(),
+            _ => {
+                use argon2::password_hash::rand_core::{OsRng, RngCore};
+
+                let mut secret = vec![0; 32];
+                OsRng.fill_bytes(&mut secret);
+                secret
+            }
+        };
+        let ttl = env::var("AFTERSHOCK_PREVIEW_TTL")
+            .ok()
+            .and_then(|x| x.parse::<u64>().ok())
+            .filter(|x| *x > 0 && *x <= MAX_TTL.as_secs())
+            .unwrap_or(24 * 60 * 60);
+
+        Self {
+            secret,
+            ttl: Duration::from_secs(ttl),
+        }
+    }
+}
+
+fn mac(payload: &[u8]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(&CONFIG.secret).expect("HMAC takes any key size");
+    mac.update(payload);
+    mac
+}
+
+/// Sign a token for `uid` that expires after `ttl`, or the configured default. `ttl` is
+/// at most [`MAX_TTL`].
+pub fn sign(
+    kind: ContentKind,
+    uid: &str,
+    ttl: Option<Duration>,
+) -> aftershock_bridge::PreviewToken {
+    let expires_at = crate::utils::now() + ttl.unwrap_or(CONFIG.ttl).as_secs() as i64;
+    let payload = format!("{}:{uid}:{expires_at}", String::from(kind));
+    let signature = mac(payload.as_bytes()).finalize().into_bytes();
+
+    aftershock_bridge::PreviewToken {
+        token: format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(payload),
+            URL_SAFE_NO_PAD.encode(signature)
+        ),
+        expires_at,
+    }
+}
+
+/// Check the signature and the expiry of `token`, returning what it points at.
+pub fn verify(token: &str) -> Result<(ContentKind, String)> {
+    let invalid = || Error::Forbidden("Invalid preview token".into());
+
+    let (payload, signature) = token.split_once('.').ok_or_else(invalid)?;
+    let payload = URL_SAFE_NO_PAD.decode(payload).map_err(|_| invalid())?;
+    let signature = URL_SAFE_NO_PAD.decode(signature).map_err(|_| invalid())?;
+    mac(&payload)
+        .verify_slice(&signature)
+        .map_err(|_| invalid())?;
+
+    let payload = String::from_utf8(payload).map_err(|_| invalid())?;
+    let mut parts = payload.splitn(3, ':');
+    let (Some(kind), Some(uid), Some(expires_at)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid());
+    };
+    let expires_at = expires_at.parse::<i64>().map_err(|_| invalid())?;
+    if expires_at < crate::utils::now() {
+        return Err(Error::Forbidden("Preview token expired".into()));
+    }
+
+    Ok((ContentKind::try_from(kind)?, uid.into()))
+}
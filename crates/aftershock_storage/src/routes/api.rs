@@ -1,15 +1,106 @@
 use crate::POOL;
 use crate::Result;
-use crate::models::UpdateContent;
-use crate::routes::worker::Worker;
-use aftershock_bridge::{NewPost, Post, PostMeta};
-use axum::{Json, extract::Path};
+use crate::backup::{self, BackupConfig, BackupStatus};
+use crate::events;
+use crate::models::{ContentKind, UpdateContent, Visibility};
+use crate::routes::worker::{Worker, WorkerBuilder};
+use aftershock_bridge::{
+    ArchiveMonth, NewPost, Paged, Post, PostMeta, PostNeighbors, PreviewToken, SearchHit, TagCount,
+    UnlockPost,
+};
+use axum::{
+    Json,
+    extract::{Path, Query},
+    http::{
+        HeaderMap, HeaderValue,
+        header::{ETAG, IF_MATCH},
+    },
+    response::{
+        IntoResponse,
+        sse::{self, KeepAlive, Sse},
+    },
+};
+use tokio_stream::{Stream, StreamExt, wrappers::BroadcastStream};
+
+pub fn etag(post: &Post) -> HeaderValue {
+    HeaderValue::from_str(&format!("\"{}\"", post.revision)).unwrap()
+}
+
+fn with_etag(post: Post) -> impl IntoResponse {
+    ([(ETAG, etag(&post))], Json(post))
+}
+
+/// The revision required by `If-Match`, `None` when any revision is accepted.
+fn if_match(headers: &HeaderMap) -> Result<Option<i32>> {
+    let Some(value) = headers.get(IF_MATCH) else {
+        return Ok(None);
+    };
+    let value = value
+        .to_str()
+        .map_err(|_| crate::error::Error::BadRequest("Malformed If-Match".into()))?
+        .trim();
+    if value == "*" {
+        return Ok(None);
+    }
+
+    value
+        .trim_start_matches("W/")
+        .trim_matches('"')
+        .parse()
+        .map(Some)
+        .map_err(|_| crate::error::Error::BadRequest(format!("Malformed If-Match {value}")))
+}
+
+/// Tell a stale revision apart from a missing content after an update matched nothing.
+fn update_failure(
+    conn: &mut crate::pool::DbConnection,
+    builder: WorkerBuilder,
+    revision: Option<i32>,
+) -> crate::error::Error {
+    let current: Option<Post> = revision
+        .and_then(|_| builder.query().build(conn))
+        .and_then(|worker| worker.load().ok())
+        .and_then(|x| x.into_iter().next());
+
+    match current {
+        Some(post) => crate::error::Error::PreconditionFailed(Box::new(post)),
+        None => crate::error::Error::NotFound("Content not found".into()),
+    }
+}
+
+/// Check `password` against the hash of a published, password protected content.
+fn unlock_content(
+    conn: &mut crate::pool::DbConnection,
+    kind: ContentKind,
+    uid: &str,
+    password: &str,
+) -> Result<()> {
+    use crate::schema::contents;
+    use diesel::prelude::*;
+
+    let password_hash = contents::table
+        .filter(contents::kind.eq(String::from(kind)))
+        .filter(contents::uid.eq(uid))
+        .filter(contents::published.eq(true))
+        .filter(contents::deleted_at.is_null())
+        .filter(contents::visibility.eq(Visibility::Protected.as_str()))
+        .select(contents::password_hash)
+        .first::<Option<String>>(conn)
+        .optional()?
+        .ok_or_else(|| crate::error::Error::NotFound("Content not found".into()))?;
+
+    match password_hash {
+        Some(hash) if crate::utils::verify_password(&hash, password) => Ok(()),
+        _ => Err(crate::error::Error::Forbidden("Wrong password".into())),
+    }
+}
 
 pub async fn get_published_posts() -> Result<Json<Vec<Post>>> {
     let conn = &mut POOL.clone().get()?;
     let ret = Worker::builder()
         .post()
-        .published_only()
+        .listed_only()
+        .redact_protected()
         .query()
         .build(conn)
         .ok_or_else(|| crate::error::Error::NotFound("Failed to build worker".into()))?
@@ -32,7 +123,7 @@ pub async fn get_published_posts_meta() -> Result<Json<Vec<PostMeta>>> {
     let conn = &mut POOL.clone().get()?;
     let ret = Worker::builder()
         .post()
-        .published_only()
+        .listed_only()
         .query()
         .build(conn)
         .ok_or_else(|| crate::error::Error::NotFound("Failed to build worker".into()))?
@@ -40,6 +131,263 @@ pub async fn get_published_posts_meta() -> Result<Json<Vec<PostMeta>>> {
     Ok(Json(ret))
 }
 
+const DEFAULT_PER_PAGE: i64 = 10;
+const MAX_PER_PAGE: i64 = 100;
+
+#[derive(serde::Deserialize)]
+pub struct PageQuery {
+    per_page: Option<i64>,
+}
+
+/// Count what `builder` matches, then load the `page`th slice of it.
+fn load_page(
+    builder: impl Fn() -> WorkerBuilder,
+    page: i64,
+    query: PageQuery,
+) -> Result<Paged<PostMeta>> {
+    let per_page = query.per_page.unwrap_or(DEFAULT_PER_PAGE);
+    if page < 1 || !(1..=MAX_PER_PAGE).contains(&per_page) {
+        return Err(crate::error::Error::BadRequest(format!(
+            "Page must be at least 1 and per_page between 1 and {MAX_PER_PAGE}"
+        )));
+    }
+
+    let conn = &mut POOL.clone().get()?;
+    let total = builder()
+        .count(conn)
+        .ok_or_else(|| crate::error::Error::NotFound("Failed to build worker".into()))??;
+    let total_pages = (total + per_page - 1) / per_page;
+    // The first page always exists, even when there is nothing to show
+    if page > total_pages.max(1) {
+        return Err(crate::error::Error::NotFound(format!("No page {page}")));
+    }
+
+    let items = builder()
+        .paginate(page, per_page)
+        .query()
+        .build(conn)
+        .ok_or_else(|| crate::error::Error::NotFound("Failed to build worker".into()))?
+        .load()?;
+
+    Ok(Paged {
+        items,
+        page,
+        per_page,
+        total,
+        total_pages,
+    })
+}
+
+pub async fn get_published_posts_meta_page(
+    Path(page): Path<i64>,
+    Query(query): Query<PageQuery>,
+) -> Result<Json<Paged<PostMeta>>> {
+    let builder = || Worker::builder().post().listed_only();
+    Ok(Json(load_page(builder, page, query)?))
+}
+
+/// Posts shown in listings: published, not unlisted and not in the trash.
+#[diesel::dsl::auto_type(no_type_alias)]
+fn listed_post() -> _ {
+    use diesel::prelude::*;
+
+    let kind: &'static str = "post";
+    let unlisted: &'static str = Visibility::Unlisted.as_str();
+    crate::schema::contents::kind
+        .eq(kind)
+        .and(crate::schema::contents::published.eq(true))
+        .and(crate::schema::contents::visibility.ne(unlisted))
+        .and(crate::schema::contents::deleted_at.is_null())
+}
+
+/// Every tag of the listed posts with the number of posts carrying it, by name.
+pub async fn get_post_tag_counts() -> Result<Json<Vec<TagCount>>> {
+    use crate::schema::{contents, contents_tags, tags};
+    use diesel::prelude::*;
+
+    let conn = &mut POOL.clone().get()?;
+    let ret = contents_tags::table
+        .inner_join(contents::table)
+        .inner_join(tags::table)
+        .filter(listed_post())
+        .group_by(tags::tag)
+        .select((tags::tag, diesel::dsl::count(contents_tags::content_id)))
+        .order(tags::tag.asc())
+        .load::<(String, i64)>(conn)?
+        .into_iter()
+        .map(|(tag, count)| TagCount { tag, count })
+        .collect();
+    Ok(Json(ret))
+}
+
+const RELATED_LIMIT: i64 = 5;
+
+/// Uids of the listed posts around a published post: the previous and next ones by
+/// creation time, then those sharing the most tags with it, newer first among equals.
+fn neighbor_uids(
+    conn: &mut crate::pool::DbConnection,
+    uid: &str,
+) -> Result<(Option<String>, Option<String>, Vec<String>)> {
+    use crate::schema::{contents, contents_tags};
+    use diesel::{dsl::count_star, prelude::*};
+
+    let (id, created_at) = contents::table
+        .filter(contents::kind.eq(String::from(ContentKind::Post)))
+        .filter(contents::uid.eq(uid))
+        .filter(contents::published.eq(true))
+        .filter(contents::deleted_at.is_null())
+        .select((contents::id, contents::created_at))
+        .first::<(i32, i64)>(conn)
+        .optional()?
+        .ok_or_else(|| crate::error::Error::NotFound("Content not found".into()))?;
+
+    // Posts created in the same second are told apart by id
+    let prev = contents::table
+        .filter(listed_post())
+        .filter(
+            contents::created_at
+                .lt(created_at)
+                .or(contents::created_at.eq(created_at).and(contents::id.lt(id))),
+        )
+        .order((contents::created_at.desc(), contents::id.desc()))
+        .select(contents::uid)
+        .first::<String>(conn)
+        .optional()?;
+    let next = contents::table
+        .filter(listed_post())
+        .filter(
+            contents::created_at
+                .gt(created_at)
+                .or(contents::created_at.eq(created_at).and(contents::id.gt(id))),
+        )
+        .order((contents::created_at.asc(), contents::id.asc()))
+        .select(contents::uid)
+        .first::<String>(conn)
+        .optional()?;
+
+    let tag_ids = contents_tags::table
+        .filter(contents_tags::content_id.eq(id))
+        .select(contents_tags::tag_id)
+        .load::<i32>(conn)?;
+    let related = contents_tags::table
+        .inner_join(contents::table)
+        .filter(listed_post())
+        .filter(contents::id.ne(id))
+        .filter(contents_tags::tag_id.eq_any(tag_ids))
+        .group_by(contents::id)
+        .order((count_star().desc(), contents::created_at.desc()))
+        .select(contents::uid)
+        .limit(RELATED_LIMIT)
+        .load::<String>(conn)?;
+
+    Ok((prev, next, related))
+}
+
+pub async fn get_post_neighbors(Path(post_uid): Path<String>) -> Result<Json<PostNeighbors>> {
+    let conn = &mut POOL.clone().get()?;
+    let (prev, next, related) = neighbor_uids(conn, &post_uid)?;
+
+    let uids = prev
+        .iter()
+        .chain(next.iter())
+        .chain(related.iter())
+        .cloned()
+        .collect();
+    let posts: Vec<PostMeta> = Worker::builder()
+        .post()
+        .listed_only()
+        .by_ids(uids)
+        .query()
+        .build(conn)
+        .ok_or_else(|| crate::error::Error::NotFound("Failed to build worker".into()))?
+        .load()?;
+    // The next post is often related too, so look posts up rather than moving them out
+    let find = |uid: &String| posts.iter().find(|x| &x.uid == uid).cloned();
+
+    Ok(Json(PostNeighbors {
+        prev: prev.as_ref().and_then(find),
+        next: next.as_ref().and_then(find),
+        related: related.iter().filter_map(find).collect(),
+    }))
+}
+
+#[derive(serde::Deserialize)]
+pub struct TimeZoneQuery {
+    /// IANA name of the timezone months are counted in, UTC by default
+    tz: Option<String>,
+}
+
+/// Listed posts per month, newest first.
+pub async fn get_post_archive(
+    Query(query): Query<TimeZoneQuery>,
+) -> Result<Json<Vec<ArchiveMonth>>> {
+    use crate::schema::contents;
+    use diesel::prelude::*;
+
+    let tz = crate::calendar::parse_tz(query.tz.as_deref())?;
+    let conn = &mut POOL.clone().get()?;
+    let timestamps = contents::table
+        .filter(listed_post())
+        .select(contents::created_at)
+        .load::<i64>(conn)?;
+    Ok(Json(crate::calendar::month_counts(timestamps, tz)))
+}
+
+fn load_period(tz: Option<&str>, year: i32, month: Option<u32>) -> Result<Vec<PostMeta>> {
+    let tz = crate::calendar::parse_tz(tz)?;
+    let (start, end) = crate::calendar::period(tz, year, month)?;
+
+    let conn = &mut POOL.clone().get()?;
+    let ret = Worker::builder()
+        .post()
+        .listed_only()
+        .created_between(start, end)
+        .query()
+        .build(conn)
+        .ok_or_else(|| crate::error::Error::NotFound("Failed to build worker".into()))?
+        .load()?;
+    Ok(ret)
+}
+
+pub async fn get_posts_meta_by_year(
+    Path(year): Path<i32>,
+    Query(query): Query<TimeZoneQuery>,
+) -> Result<Json<Vec<PostMeta>>> {
+    Ok(Json(load_period(query.tz.as_deref(), year, None)?))
+}
+
+pub async fn get_posts_meta_by_month(
+    Path((year, month)): Path<(i32, u32)>,
+    Query(query): Query<TimeZoneQuery>,
+) -> Result<Json<Vec<PostMeta>>> {
+    Ok(Json(load_period(query.tz.as_deref(), year, Some(month))?))
+}
+
+#[derive(serde::Deserialize)]
+pub struct SearchQuery {
+    #[serde(default)]
+    q: String,
+}
+
+pub async fn search_posts(Query(query): Query<SearchQuery>) -> Result<Json<Vec<SearchHit>>> {
+    let terms = crate::search::terms(&query.q);
+    if terms.is_empty() {
+        return Ok(Json(vec![]));
+    }
+
+    let conn = &mut POOL.clone().get()?;
+    let posts: Vec<Post> = Worker::builder()
+        .post()
+        .listed_only()
+        .redact_protected()
+        .search(terms.clone())
+        .query()
+        .build(conn)
+        .ok_or_else(|| crate::error::Error::NotFound("Failed to build worker".into()))?
+        .load()?;
+    Ok(Json(crate::search::hits(posts, &terms)))
+}
+
 pub async fn get_all_posts_meta() -> Result<Json<Vec<PostMeta>>> {
     let conn = &mut POOL.clone().get()?;
     let ret = Worker::builder()
@@ -66,33 +414,59 @@ pub async fn create_content(Json(post): Json<NewPost>) -> Result<Json<Post>> {
     }
 }
 
-pub async fn get_post_by_uid(Path(post_uid): Path<String>) -> Result<Json<Post>> {
+pub async fn get_post_by_uid(Path(post_uid): Path<String>) -> Result<impl IntoResponse> {
     let conn = &mut POOL.clone().get()?;
     let ret: Vec<Post> = Worker::builder()
         .post()
         .published_only()
+        .redact_protected()
         .by_id(post_uid)
         .query()
         .build(conn)
         .ok_or_else(|| crate::error::Error::NotFound("Failed to build worker".into()))?
         .load()?;
     match ret.first() {
-        Some(post) => Ok(Json(post.clone())),
+        Some(post) => Ok(with_etag(post.clone())),
         None => Err(crate::error::Error::NotFound("Content not found".into())),
     }
 }
 
 pub async fn update_post_by_uid(
     Path(post_uid): Path<String>,
+    headers: HeaderMap,
     Json(updated_set): Json<aftershock_bridge::UpdatePost>,
-) -> Result<Json<Post>> {
+) -> Result<impl IntoResponse> {
     let conn = &mut POOL.clone().get()?;
-    let update_content: UpdateContent = updated_set.into();
+    let update_content: UpdateContent = updated_set.try_into()?;
+    let revision = if_match(&headers)?;
+
+    let mut builder = Worker::builder()
+        .post()
+        .by_id(post_uid.clone())
+        .update(update_content);
+    if let Some(revision) = revision {
+        builder = builder.if_revision(revision);
+    }
+    let ret: Vec<Post> = builder
+        .build(conn)
+        .ok_or_else(|| crate::error::Error::NotFound("Failed to build worker".into()))?
+        .load()?;
+    match ret.first() {
+        Some(post) => Ok(with_etag(post.clone())),
+        None => Err(update_failure(
+            conn,
+            Worker::builder().post().by_id(post_uid),
+            revision,
+        )),
+    }
+}
 
+pub async fn delete_post_by_uid(Path(post_uid): Path<String>) -> Result<Json<Post>> {
+    let conn = &mut POOL.clone().get()?;
     let ret: Vec<Post> = Worker::builder()
         .post()
         .by_id(post_uid)
-        .update(update_content)
+        .delete()
         .build(conn)
         .ok_or_else(|| crate::error::Error::NotFound("Failed to build worker".into()))?
         .load()?;
@@ -102,17 +476,23 @@ pub async fn update_post_by_uid(
     }
 }
 
-pub async fn delete_post_by_uid(Path(post_uid): Path<String>) -> Result<Json<Post>> {
+pub async fn unlock_post_by_uid(
+    Path(post_uid): Path<String>,
+    Json(unlock): Json<UnlockPost>,
+) -> Result<impl IntoResponse> {
     let conn = &mut POOL.clone().get()?;
+    unlock_content(conn, ContentKind::Post, &post_uid, &unlock.password)?;
+
     let ret: Vec<Post> = Worker::builder()
         .post()
+        .published_only()
         .by_id(post_uid)
-        .delete()
+        .query()
         .build(conn)
         .ok_or_else(|| crate::error::Error::NotFound("Failed to build worker".into()))?
         .load()?;
     match ret.first() {
-        Some(post) => Ok(Json(post.clone())),
+        Some(post) => Ok(with_etag(post.clone())),
         None => Err(crate::error::Error::NotFound("Content not found".into())),
     }
 }
@@ -121,7 +501,8 @@ pub async fn get_published_pages() -> Result<Json<Vec<Post>>> {
     let conn = &mut POOL.clone().get()?;
     let ret = Worker::builder()
         .page()
-        .published_only()
+        .listed_only()
+        .redact_protected()
         .query()
         .build(conn)
         .ok_or_else(|| crate::error::Error::NotFound("Failed to build worker".into()))?
@@ -133,7 +514,7 @@ pub async fn get_published_pages_meta() -> Result<Json<Vec<PostMeta>>> {
     let conn = &mut POOL.clone().get()?;
     let ret = Worker::builder()
         .page()
-        .published_only()
+        .listed_only()
         .query()
         .build(conn)
         .ok_or_else(|| crate::error::Error::NotFound("Failed to build worker".into()))?
@@ -163,33 +544,59 @@ pub async fn get_all_pages_meta() -> Result<Json<Vec<PostMeta>>> {
     Ok(Json(ret))
 }
 
-pub async fn get_page_by_uid(Path(page_uid): Path<String>) -> Result<Json<Post>> {
+pub async fn get_page_by_uid(Path(page_uid): Path<String>) -> Result<impl IntoResponse> {
     let conn = &mut POOL.clone().get()?;
     let ret: Vec<Post> = Worker::builder()
         .page()
         .published_only()
+        .redact_protected()
         .by_id(page_uid.to_string())
         .query()
         .build(conn)
         .ok_or_else(|| crate::error::Error::NotFound("Failed to build worker".into()))?
         .load()?;
     match ret.first() {
-        Some(post) => Ok(Json(post.clone())),
+        Some(post) => Ok(with_etag(post.clone())),
         None => Err(crate::error::Error::NotFound("Content not found".into())),
     }
 }
 
 pub async fn update_page_by_uid(
     Path(page_uid): Path<String>,
+    headers: HeaderMap,
     Json(updated_set): Json<aftershock_bridge::UpdatePost>,
-) -> Result<Json<Post>> {
+) -> Result<impl IntoResponse> {
     let conn = &mut POOL.clone().get()?;
-    let update_content: UpdateContent = updated_set.into();
+    let update_content: UpdateContent = updated_set.try_into()?;
+    let revision = if_match(&headers)?;
+
+    let mut builder = Worker::builder()
+        .page()
+        .by_id(page_uid.clone())
+        .update(update_content);
+    if let Some(revision) = revision {
+        builder = builder.if_revision(revision);
+    }
+    let ret: Vec<Post> = builder
+        .build(conn)
+        .ok_or_else(|| crate::error::Error::NotFound("Failed to build worker".into()))?
+        .load()?;
+    match ret.first() {
+        Some(post) => Ok(with_etag(post.clone())),
+        None => Err(update_failure(
+            conn,
+            Worker::builder().page().by_id(page_uid),
+            revision,
+        )),
+    }
+}
 
+pub async fn delete_page_by_uid(Path(page_uid): Path<String>) -> Result<Json<Post>> {
+    let conn = &mut POOL.clone().get()?;
     let ret: Vec<Post> = Worker::builder()
         .page()
         .by_id(page_uid)
-        .update(update_content)
+        .delete()
         .build(conn)
         .ok_or_else(|| crate::error::Error::NotFound("Failed to build worker".into()))?
         .load()?;
@@ -199,12 +606,171 @@ pub async fn update_page_by_uid(
     }
 }
 
-pub async fn delete_page_by_uid(Path(page_uid): Path<String>) -> Result<Json<Post>> {
+pub async fn unlock_page_by_uid(
+    Path(page_uid): Path<String>,
+    Json(unlock): Json<UnlockPost>,
+) -> Result<impl IntoResponse> {
     let conn = &mut POOL.clone().get()?;
+    unlock_content(conn, ContentKind::Page, &page_uid, &unlock.password)?;
+
     let ret: Vec<Post> = Worker::builder()
         .page()
+        .published_only()
         .by_id(page_uid)
-        .delete()
+        .query()
+        .build(conn)
+        .ok_or_else(|| crate::error::Error::NotFound("Failed to build worker".into()))?
+        .load()?;
+    match ret.first() {
+        Some(post) => Ok(with_etag(post.clone())),
+        None => Err(crate::error::Error::NotFound("Content not found".into())),
+    }
+}
+
+// Preview handlers
+
+#[derive(serde::Deserialize)]
+pub struct PreviewQuery {
+    /// Lifetime of the token in seconds
+    ttl: Option<u64>,
+}
+
+fn kind_builder(kind: &ContentKind) -> WorkerBuilder {
+    match kind {
+        ContentKind::Post => Worker::builder().post(),
+        ContentKind::Page => Worker::builder().page(),
+    }
+}
+
+fn create_preview(kind: ContentKind, uid: String, query: PreviewQuery) -> Result<PreviewToken> {
+    let conn = &mut POOL.clone().get()?;
+    let ret: Vec<PostMeta> = kind_builder(&kind)
+        .by_id(uid.clone())
+        .query()
+        .build(conn)
+        .ok_or_else(|| crate::error::Error::NotFound("Failed to build worker".into()))?
+        .load()?;
+    if ret.is_empty() {
+        return Err(crate::error::Error::NotFound("Content not found".into()));
+    }
+
+    let ttl = query.ttl.map(std::time::Duration::from_secs);
+    if ttl.is_some_and(|ttl| ttl > crate::preview::MAX_TTL) {
+        return Err(crate::error::Error::BadRequest(format!(
+            "A preview lives at most {} seconds",
+            crate::preview::MAX_TTL.as_secs()
+        )));
+    }
+    Ok(crate::preview::sign(kind, &uid, ttl))
+}
+
+pub async fn create_post_preview(
+    Path(post_uid): Path<String>,
+    Query(query): Query<PreviewQuery>,
+) -> Result<Json<PreviewToken>> {
+    Ok(Json(create_preview(ContentKind::Post, post_uid, query)?))
+}
+
+pub async fn create_page_preview(
+    Path(page_uid): Path<String>,
+    Query(query): Query<PreviewQuery>,
+) -> Result<Json<PreviewToken>> {
+    Ok(Json(create_preview(ContentKind::Page, page_uid, query)?))
+}
+
+/// The content behind a preview token, published or not.
+pub async fn get_preview(Path(token): Path<String>) -> Result<Json<Post>> {
+    let (kind, uid) = crate::preview::verify(&token)?;
+
+    let conn = &mut POOL.clone().get()?;
+    let ret: Vec<Post> = kind_builder(&kind)
+        .by_id(uid)
+        .query()
+        .build(conn)
+        .ok_or_else(|| crate::error::Error::NotFound("Failed to build worker".into()))?
+        .load()?;
+    match ret.into_iter().next() {
+        Some(post) => Ok(Json(post)),
+        None => Err(crate::error::Error::NotFound("Content not found".into())),
+    }
+}
+
+// Trash handlers
+pub async fn get_trashed_posts_meta() -> Result<Json<Vec<PostMeta>>> {
+    let conn = &mut POOL.clone().get()?;
+    let ret = Worker::builder()
+        .post()
+        .trashed()
+        .query()
+        .build(conn)
+        .ok_or_else(|| crate::error::Error::NotFound("Failed to build worker".into()))?
+        .load()?;
+    Ok(Json(ret))
+}
+
+pub async fn restore_post_by_uid(Path(post_uid): Path<String>) -> Result<Json<Post>> {
+    let conn = &mut POOL.clone().get()?;
+    let ret: Vec<Post> = Worker::builder()
+        .post()
+        .by_id(post_uid)
+        .restore()
+        .build(conn)
+        .ok_or_else(|| crate::error::Error::NotFound("Failed to build worker".into()))?
+        .load()?;
+    match ret.first() {
+        Some(post) => Ok(Json(post.clone())),
+        None => Err(crate::error::Error::NotFound("Content not found".into())),
+    }
+}
+
+pub async fn purge_post_by_uid(Path(post_uid): Path<String>) -> Result<Json<Post>> {
+    let conn = &mut POOL.clone().get()?;
+    let ret: Vec<Post> = Worker::builder()
+        .post()
+        .by_id(post_uid)
+        .purge()
+        .build(conn)
+        .ok_or_else(|| crate::error::Error::NotFound("Failed to build worker".into()))?
+        .load()?;
+    match ret.first() {
+        Some(post) => Ok(Json(post.clone())),
+        None => Err(crate::error::Error::NotFound("Content not found".into())),
+    }
+}
+
+pub async fn get_trashed_pages_meta() -> Result<Json<Vec<PostMeta>>> {
+    let conn = &mut POOL.clone().get()?;
+    let ret = Worker::builder()
+        .page()
+        .trashed()
+        .query()
+        .build(conn)
+        .ok_or_else(|| crate::error::Error::NotFound("Failed to build worker".into()))?
+        .load()?;
+    Ok(Json(ret))
+}
+
+pub async fn restore_page_by_uid(Path(page_uid): Path<String>) -> Result<Json<Post>> {
+    let conn = &mut POOL.clone().get()?;
+    let ret: Vec<Post> = Worker::builder()
+        .page()
+        .by_id(page_uid)
+        .restore()
+        .build(conn)
+        .ok_or_else(|| crate::error::Error::NotFound("Failed to build worker".into()))?
+        .load()?;
+    match ret.first() {
+        Some(post) => Ok(Json(post.clone())),
+        None => Err(crate::error::Error::NotFound("Content not found".into())),
+    }
+}
+
+pub async fn purge_page_by_uid(Path(page_uid): Path<String>) -> Result<Json<Post>> {
+    let conn = &mut POOL.clone().get()?;
+    let ret: Vec<Post> = Worker::builder()
+        .page()
+        .by_id(page_uid)
+        .purge()
         .build(conn)
         .ok_or_else(|| crate::error::Error::NotFound("Failed to build worker".into()))?
         .load()?;
@@ -219,7 +785,8 @@ pub async fn get_published_posts_by_tag(Path(tag): Path<String>) -> Result<Json<
     let conn = &mut POOL.clone().get()?;
     let ret = Worker::builder()
         .post()
-        .published_only()
+        .listed_only()
+        .redact_protected()
         .by_tag(tag)
         .query()
         .build(conn)
@@ -234,7 +801,7 @@ pub async fn get_published_posts_meta_by_tag(
     let conn = &mut POOL.clone().get()?;
     let ret = Worker::builder()
         .post()
-        .published_only()
+        .listed_only()
         .by_tag(tag)
         .query()
         .build(conn)
@@ -255,6 +822,14 @@ pub async fn get_all_posts_by_tag(Path(tag): Path<String>) -> Result<Json<Vec<Po
     Ok(Json(ret))
 }
 
+pub async fn get_published_posts_meta_by_tag_page(
+    Path((tag, page)): Path<(String, i64)>,
+    Query(query): Query<PageQuery>,
+) -> Result<Json<Paged<PostMeta>>> {
+    let builder = || Worker::builder().post().listed_only().by_tag(tag.clone());
+    Ok(Json(load_page(builder, page, query)?))
+}
+
 pub async fn get_all_posts_meta_by_tag(Path(tag): Path<String>) -> Result<Json<Vec<PostMeta>>> {
     let conn = &mut POOL.clone().get()?;
     let ret = Worker::builder()
@@ -272,7 +847,8 @@ pub async fn get_published_pages_by_tag(Path(tag): Path<String>) -> Result<Json<
     let conn = &mut POOL.clone().get()?;
     let ret = Worker::builder()
         .page()
-        .published_only()
+        .listed_only()
+        .redact_protected()
         .by_tag(tag)
         .query()
         .build(conn)
@@ -287,7 +863,7 @@ pub async fn get_published_pages_meta_by_tag(
     let conn = &mut POOL.clone().get()?;
     let ret = Worker::builder()
         .page()
-        .published_only()
+        .listed_only()
         .by_tag(tag)
         .query()
         .build(conn)
@@ -319,3 +895,57 @@ pub async fn get_all_pages_meta_by_tag(Path(tag): Path<String>) -> Result<Json<V
         .load()?;
     Ok(Json(ret))
 }
+
+// Content change events
+pub async fn stream_events(
+    headers: HeaderMap,
+) -> Result<Sse<impl Stream<Item = std::result::Result<sse::Event, axum::Error>>>> {
+    let last_event_id = headers
+        .get("last-event-id")
+        .and_then(|x| x.to_str().ok())
+        .and_then(|x| x.trim().parse::<i32>().ok());
+
+    // Subscribe before reading the log so nothing slips through in between
+    let receiver = events::subscribe();
+    let backlog = match last_event_id {
+        Some(last_event_id) => {
+            let conn = &mut POOL.clone().get()?;
+            events::since(conn, last_event_id)?
+        }
+        None => vec![],
+    };
+    let replayed_until = backlog
+        .last()
+        .map(|x| x.id)
+        .or(last_event_id)
+        .unwrap_or_default();
+
+    // A lagging subscriber ends the stream, the client reconnects and resumes from the log
+    let live = BroadcastStream::new(receiver)
+        .take_while(|x| x.is_ok())
+        .filter_map(move |x| x.ok().filter(|x| x.id > replayed_until));
+
+    let stream = tokio_stream::iter(backlog).chain(live).map(|x| {
+        sse::Event::default()
+            .id(x.id.to_string())
+            .event(&x.event)
+            .json_data(&x)
+    });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+// Backups
+pub async fn create_backup() -> Result<Json<BackupStatus>> {
+    let config = BackupConfig::from_env();
+    let status = tokio::task::spawn_blocking(move || backup::run(&config))
+        .await
+        .map_err(|e| crate::error::Error::BackupError(e.to_string()))??;
+    Ok(Json(status))
+}
+
+pub async fn get_backup_status() -> Result<Json<BackupStatus>> {
+    backup::last_status()
+        .map(Json)
+        .ok_or_else(|| crate::error::Error::NotFound("No backup has been taken yet".into()))
+}
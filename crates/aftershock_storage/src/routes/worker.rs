@@ -3,7 +3,8 @@ use r2d2::PooledConnection;
 
 use crate::{
     Result,
-    models::{Content, ContentTag, IntoPost, Tag, UpdateContent},
+    events::{self, EventKind},
+    models::{Content, ContentTag, IntoPost, Tag, UpdateContent, Visibility},
     schema::{self},
 };
 
@@ -19,21 +20,37 @@ pub enum ContentKind {
 }
 
 pub enum PublishState {
+    /// Published and shown in listings, unlisted contents are left out
+    Listed,
+    /// Published, whether listed or not
     Published,
     All,
 }
 
+pub enum TrashState {
+    Live,
+    Trashed,
+}
+
 pub enum Filter {
     All,
     Id(String),
+    Ids(Vec<String>),
     Name(String),
     Tag(String),
+    /// Every term appears in the title, the summary or the body
+    Search(Vec<String>),
+    /// Created in `[start, end)`, unix timestamps
+    CreatedBetween(i64, i64),
+    DeletedBefore(i64),
 }
 
 pub enum Action {
     Create(aftershock_bridge::NewPost),
     Update(UpdateContent),
     Delete,
+    Restore,
+    Purge,
     Query,
 }
 
@@ -62,7 +79,12 @@ impl Worker<'_> {
 pub struct WorkerBuilder {
     target_kind: Option<TargetKind>,
     publish_state: Option<PublishState>,
+    trash_state: Option<TrashState>,
     filter: Option<Filter>,
+    revision: Option<i32>,
+    redact_protected: bool,
+    /// `(offset, limit)` of the queried contents, newest first
+    page: Option<(i64, i64)>,
     action: Option<Action>,
 }
 
@@ -77,7 +99,11 @@ impl WorkerBuilder {
         Self {
             target_kind: None,
             publish_state: Some(PublishState::All),
+            trash_state: Some(TrashState::Live),
             filter: Some(Filter::All),
+            revision: None,
+            redact_protected: false,
+            page: None,
             action: None,
         }
     }
@@ -91,7 +117,7 @@ impl WorkerBuilder {
             Action::Create(post) => Box::new(move |c| {
                 use crate::schema::{contents, contents_tags, tags};
 
-                let new_content: crate::models::NewContent = (&post).into();
+                let new_content: crate::models::NewContent = (&post).try_into()?;
 
                 let content = diesel::insert_into(contents::table)
                     .values(&new_content)
@@ -120,16 +146,39 @@ impl WorkerBuilder {
                     .values(&ct)
                     .execute(&mut *c)?;
 
-                Ok(vec![(content, tags).into_post()])
+                let ret = vec![(content, tags).into_post()];
+                events::broadcast(events::record(c, EventKind::Created, &ret)?);
+
+                Ok(ret)
             }),
             action => {
                 let query = schema::contents::table
                     .filter(Self::filter_by_target_kind(self.target_kind?))
                     .filter(Self::filter_by_filter(self.filter?))
-                    .filter(Self::filter_by_publish_state(self.publish_state?));
+                    .filter(Self::filter_by_publish_state(self.publish_state?))
+                    .filter(Self::filter_by_trash_state(self.trash_state?))
+                    .filter(Self::filter_by_revision(self.revision));
+                let redact_protected = self.redact_protected;
+                let page = self.page;
                 match action {
-                    Action::Query => Box::new(|c| {
-                        let contents = query.select(Content::as_select()).load(&mut *c)?;
+                    Action::Query => Box::new(move |c| {
+                        let mut query = query.select(Content::as_select()).into_boxed();
+                        if let Some((offset, limit)) = page {
+                            use crate::schema::contents;
+
+                            query = query
+                                .order((contents::created_at.desc(), contents::id.desc()))
+                                .offset(offset)
+                                .limit(limit);
+                        }
+                        let mut contents = query.load(&mut *c)?;
+
+                        if redact_protected {
+                            contents
+                                .iter_mut()
+                                .filter(|x| x.effective_visibility() == Visibility::Protected)
+                                .for_each(|x| x.body.clear());
+                        }
 
                         let tags = Self::get_tags_from_contents(&contents)(&mut *c)?;
 
@@ -140,24 +189,78 @@ impl WorkerBuilder {
                     Action::Update(mut update_content) => Box::new(|c| {
                         let now = crate::utils::now();
                         update_content.updated_at = Some(now);
-                        if update_content.published.is_some_and(|x| x) {
+                        let event = if update_content.published.is_some_and(|x| x) {
                             update_content.created_at = Some(now);
-                        }
+                            EventKind::Published
+                        } else {
+                            EventKind::Updated
+                        };
+
+                        let protect = update_content.visibility.as_deref()
+                            == Some(Visibility::Protected.as_str());
 
-                        let query = diesel::update(query)
-                            .set(update_content)
+                        let (ret, recorded) =
+                            c.transaction::<_, crate::error::Error, _>(|conn| {
+                                let query = diesel::update(query)
+                                    .set((
+                                        update_content,
+                                        schema::contents::revision
+                                            .eq(schema::contents::revision + 1),
+                                    ))
+                                    .returning(Content::as_returning())
+                                    .get_results(conn)?;
+
+                                // Nobody could ever unlock a protected content without a password
+                                if protect && query.iter().any(|x| x.password_hash.is_none()) {
+                                    return Err(crate::error::Error::BadRequest(
+                                        "Protected contents need a password".into(),
+                                    ));
+                                }
+
+                                let tags = Self::get_tags_from_contents(&query)(conn)?;
+
+                                //TODO: update tags
+
+                                let ret = Self::combine_content_tags(query, tags);
+                                let recorded = events::record(conn, event, &ret)?;
+
+                                Ok((ret, recorded))
+                            })?;
+                        events::broadcast(recorded);
+
+                        Ok(ret)
+                    }),
+                    Action::Delete => Box::new(|c| {
+                        use crate::schema::contents;
+
+                        let content = diesel::update(query)
+                            .set(contents::deleted_at.eq(Some(crate::utils::now())))
                             .returning(Content::as_returning())
                             .get_results(&mut *c)?;
 
-                        let tags = Self::get_tags_from_contents(&query)(&mut *c)?;
+                        let tags = Self::get_tags_from_contents(&content)(&mut *c)?;
+
+                        let ret = Self::combine_content_tags(content, tags);
+                        events::broadcast(events::record(c, EventKind::Deleted, &ret)?);
+
+                        Ok(ret)
+                    }),
+                    Action::Restore => Box::new(|c| {
+                        use crate::schema::contents;
+
+                        let content = diesel::update(query)
+                            .set(contents::deleted_at.eq(None::<i64>))
+                            .returning(Content::as_returning())
+                            .get_results(&mut *c)?;
 
-                        //TODO: update tags
+                        let tags = Self::get_tags_from_contents(&content)(&mut *c)?;
 
-                        let ret = Self::combine_content_tags(query, tags);
+                        let ret = Self::combine_content_tags(content, tags);
+                        events::broadcast(events::record(c, EventKind::Restored, &ret)?);
 
                         Ok(ret)
                     }),
-                    Action::Delete => Box::new(|c| {
+                    Action::Purge => Box::new(|c| {
                         use crate::schema::contents_tags;
 
                         let (content, tags) =
@@ -193,6 +296,21 @@ impl WorkerBuilder {
         Some(Worker { conn, action })
     }
 
+    /// Number of contents matching the filters, pagination aside.
+    pub fn count(self, conn: BorrowedConnection<'_>) -> Option<Result<i64>> {
+        let ret = schema::contents::table
+            .filter(Self::filter_by_target_kind(self.target_kind?))
+            .filter(Self::filter_by_filter(self.filter?))
+            .filter(Self::filter_by_publish_state(self.publish_state?))
+            .filter(Self::filter_by_trash_state(self.trash_state?))
+            .filter(Self::filter_by_revision(self.revision))
+            .count()
+            .get_result(conn)
+            .map_err(|e| e.into());
+
+        Some(ret)
+    }
+
     pub fn post(mut self) -> Self {
         self.target_kind = Some(TargetKind::Post);
         self
@@ -208,6 +326,11 @@ impl WorkerBuilder {
         self
     }
 
+    pub fn by_ids(mut self, ids: Vec<String>) -> Self {
+        self.filter = Some(Filter::Ids(ids));
+        self
+    }
+
     pub fn by_name(mut self, name: String) -> Self {
         self.filter = Some(Filter::Name(name));
         self
@@ -218,11 +341,54 @@ impl WorkerBuilder {
         self
     }
 
+    pub fn search(mut self, terms: Vec<String>) -> Self {
+        self.filter = Some(Filter::Search(terms));
+        self
+    }
+
+    pub fn created_between(mut self, start: i64, end: i64) -> Self {
+        self.filter = Some(Filter::CreatedBetween(start, end));
+        self
+    }
+
+    pub fn deleted_before(mut self, timestamp: i64) -> Self {
+        self.filter = Some(Filter::DeletedBefore(timestamp));
+        self
+    }
+
     pub fn published_only(mut self) -> Self {
         self.publish_state = Some(PublishState::Published);
         self
     }
 
+    pub fn listed_only(mut self) -> Self {
+        self.publish_state = Some(PublishState::Listed);
+        self
+    }
+
+    /// Leave the body of password protected contents out of query results
+    pub fn redact_protected(mut self) -> Self {
+        self.redact_protected = true;
+        self
+    }
+
+    /// Only query the `page`th (from 1) slice of `per_page` contents, newest first
+    pub fn paginate(mut self, page: i64, per_page: i64) -> Self {
+        self.page = Some(((page - 1).max(0) * per_page, per_page));
+        self
+    }
+
+    /// Only match contents still at `revision`, used for optimistic concurrency control
+    pub fn if_revision(mut self, revision: i32) -> Self {
+        self.revision = Some(revision);
+        self
+    }
+
+    pub fn trashed(mut self) -> Self {
+        self.trash_state = Some(TrashState::Trashed);
+        self
+    }
+
     pub fn query(mut self) -> Self {
         self.action = Some(Action::Query);
         self
@@ -238,6 +404,20 @@ impl WorkerBuilder {
         self
     }
 
+    /// Restore trashed contents, implies [`WorkerBuilder::trashed`]
+    pub fn restore(mut self) -> Self {
+        self.trash_state = Some(TrashState::Trashed);
+        self.action = Some(Action::Restore);
+        self
+    }
+
+    /// Permanently remove trashed contents, implies [`WorkerBuilder::trashed`]
+    pub fn purge(mut self) -> Self {
+        self.trash_state = Some(TrashState::Trashed);
+        self.action = Some(Action::Purge);
+        self
+    }
+
     pub fn update(mut self, content: UpdateContent) -> Self {
         self.action = Some(Action::Update(content));
         self
@@ -267,6 +447,7 @@ impl WorkerBuilder {
         match filter {
             Filter::All => Box::new(schema::contents::title.is_not_null()),
             Filter::Id(id) => Box::new(schema::contents::uid.like(id)),
+            Filter::Ids(ids) => Box::new(schema::contents::uid.eq_any(ids)),
             Filter::Name(name) => Box::new(schema::contents::title.eq(name)),
             Filter::Tag(tag) => Box::new(
                 schema::contents::id.eq_any(
@@ -276,6 +457,34 @@ impl WorkerBuilder {
                         .select(schema::contents_tags::content_id),
                 ),
             ),
+            Filter::Search(terms) => terms.into_iter().fold(
+                Box::new(schema::contents::title.is_not_null()),
+                |acc, term| {
+                    let pattern = format!(
+                        "%{}%",
+                        term.replace('\\', "\\\\")
+                            .replace('%', "\\%")
+                            .replace('_', "\\_")
+                    );
+                    let found = schema::contents::title
+                        .like(pattern.clone())
+                        .escape('\\')
+                        .or(schema::contents::body.like(pattern.clone()).escape('\\'))
+                        .or(schema::contents::summary
+                            .like(pattern)
+                            .escape('\\')
+                            .assume_not_null());
+                    Box::new(acc.and(found)) as WorkerBuilderInnerFilter
+                },
+            ),
+            Filter::CreatedBetween(start, end) => Box::new(
+                schema::contents::created_at
+                    .ge(start)
+                    .and(schema::contents::created_at.lt(end)),
+            ),
+            Filter::DeletedBefore(timestamp) => {
+                Box::new(schema::contents::deleted_at.le(timestamp).assume_not_null())
+            }
         }
     }
 
@@ -283,11 +492,34 @@ impl WorkerBuilder {
         use crate::schema;
 
         match publish_state {
+            PublishState::Listed => Box::new(
+                schema::contents::published
+                    .eq(true)
+                    .and(schema::contents::visibility.ne(Visibility::Unlisted.as_str())),
+            ),
             PublishState::Published => Box::new(schema::contents::published.eq(true)),
             PublishState::All => Box::new(schema::contents::published.is_not_null()),
         }
     }
 
+    fn filter_by_revision(revision: Option<i32>) -> WorkerBuilderInnerFilter {
+        use crate::schema;
+
+        match revision {
+            Some(revision) => Box::new(schema::contents::revision.eq(revision)),
+            None => Box::new(schema::contents::revision.is_not_null()),
+        }
+    }
+
+    fn filter_by_trash_state(trash_state: TrashState) -> WorkerBuilderInnerFilter {
+        use crate::schema;
+
+        match trash_state {
+            TrashState::Live => Box::new(schema::contents::deleted_at.is_null()),
+            TrashState::Trashed => Box::new(schema::contents::deleted_at.is_not_null()),
+        }
+    }
+
     fn get_tags_from_contents<'a>(
         contents: &'a [Content],
     ) -> impl FnOnce(BorrowedConnection<'a>) -> Result<Vec<Vec<Tag>>> {
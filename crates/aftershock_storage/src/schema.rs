@@ -11,6 +11,13 @@ diesel::table! {
         published -> Bool,
         uid -> Text,
         summary -> Nullable<Text>,
+        deleted_at -> Nullable<BigInt>,
+        revision -> Integer,
+        visibility -> Text,
+        password_hash -> Nullable<Text>,
+        nav_order -> Nullable<Integer>,
+        nav_title -> Nullable<Text>,
+        toc -> Nullable<Text>,
     }
 }
 
@@ -21,6 +28,16 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    events (id) {
+        id -> Integer,
+        event -> Text,
+        uid -> Text,
+        kind -> Text,
+        created_at -> BigInt,
+    }
+}
+
 diesel::table! {
     tags (id) {
         id -> Integer,
@@ -31,4 +48,4 @@ diesel::table! {
 diesel::joinable!(contents_tags -> contents (content_id));
 diesel::joinable!(contents_tags -> tags (tag_id));
 
-diesel::allow_tables_to_appear_in_same_query!(contents, contents_tags, tags,);
+diesel::allow_tables_to_appear_in_same_query!(contents, contents_tags, events, tags,);
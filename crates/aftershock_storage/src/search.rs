@@ -0,0 +1,155 @@
+//! Full text search over the published posts.
+//!
+//! Bodies are stored as rendered HTML, so the database only narrows the candidates down
+//! with `LIKE`. Matches are confirmed here against the text without markup, which keeps
+//! `span` or `class` from matching every highlighted code block, and snippets are cut
+//! from the same text.
+
+use aftershock_bridge::{Post, PostMeta, SearchHit, SnippetPart};
+
+/// Characters of context kept before the first match in a snippet
+const SNIPPET_BEFORE: usize = 40;
+/// Length of a snippet in characters
+const SNIPPET_LENGTH: usize = 160;
+/// Search terms beyond this are ignored
+pub const MAX_TERMS: usize = 8;
+
+/// Split a query into lowercase terms, quotes keep spaces inside a term.
+pub fn terms(query: &str) -> Vec<String> {
+    let mut ret = vec![];
+    for (index, part) in query.split('"').enumerate() {
+        if index % 2 == 1 {
+            ret.push(part.trim().to_lowercase());
+        } else {
+            ret.extend(part.split_whitespace().map(|x| x.to_lowercase()));
+        }
+    }
+    ret.retain(|x| !x.is_empty());
+    ret.dedup();
+    ret.truncate(MAX_TERMS);
+    ret
+}
+
+/// Turn the posts the database found into hits, dropping those that only matched markup.
+pub fn hits(posts: Vec<Post>, terms: &[String]) -> Vec<SearchHit> {
+    let mut ret: Vec<SearchHit> = posts
+        .into_iter()
+        .filter_map(|post| {
+            let text = strip_html(&post.body);
+            let summary = post.summary.clone().unwrap_or_default();
+            let all_found = terms.iter().all(|term| {
+                [&post.title, &summary, &text]
+                    .iter()
+                    .any(|x| !find(x, term).is_empty())
+            });
+            if !all_found {
+                return None;
+            }
+
+            // Protected bodies are redacted before they get here, the summary stands in
+            let source = match terms.iter().any(|term| !find(&text, term).is_empty()) {
+                true => text,
+                false => summary,
+            };
+            Some(SearchHit {
+                snippet: snippet(&source, terms),
+                post: PostMeta::from(post),
+            })
+        })
+        .collect();
+
+    ret.sort_by_key(|x| std::cmp::Reverse(x.post.created_at));
+    ret
+}
+
+/// Text content of an HTML fragment, whitespace collapsed.
+pub fn strip_html(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => {
+                in_tag = true;
+                text.push(' ');
+            }
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    let text = text
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&");
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn lower(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+/// Char ranges of every case-insensitive occurrence of `term` in `text`.
+fn find(text: &str, term: &str) -> Vec<(usize, usize)> {
+    let text: Vec<char> = text.chars().map(lower).collect();
+    let term: Vec<char> = term.chars().map(lower).collect();
+    if term.is_empty() || term.len() > text.len() {
+        return vec![];
+    }
+
+    let mut ret = vec![];
+    let mut start = 0;
+    while start + term.len() <= text.len() {
+        if text[start..start + term.len()] == term[..] {
+            ret.push((start, start + term.len()));
+            start += term.len();
+        } else {
+            start += 1;
+        }
+    }
+    ret
+}
+
+/// Cut a window of `text` around the first match and mark every term inside it.
+fn snippet(text: &str, terms: &[String]) -> Vec<SnippetPart> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut ranges: Vec<(usize, usize)> = terms.iter().flat_map(|x| find(text, x)).collect();
+    ranges.sort();
+
+    let begin = ranges
+        .first()
+        .map(|x| x.0.saturating_sub(SNIPPET_BEFORE))
+        .unwrap_or_default();
+    let end = (begin + SNIPPET_LENGTH).min(chars.len());
+    let collect = |from: usize, to: usize| chars[from..to].iter().collect::<String>();
+
+    let mut ret = vec![];
+    let mut push = |text: String, highlight: bool| {
+        if !text.is_empty() {
+            ret.push(SnippetPart { text, highlight });
+        }
+    };
+
+    if begin > 0 {
+        push("…".into(), false);
+    }
+    let mut cursor = begin;
+    for (start, stop) in ranges {
+        // Overlapping terms are merged into the earlier one
+        if start < cursor || stop > end {
+            continue;
+        }
+        push(collect(cursor, start), false);
+        push(collect(start, stop), true);
+        cursor = stop;
+    }
+    push(collect(cursor, end), false);
+    if end < chars.len() {
+        push("…".into(), false);
+    }
+
+    ret
+}
@@ -0,0 +1,69 @@
+//! Retention of soft deleted contents.
+
+use std::{env, time::Duration};
+
+use crate::{POOL, Result, error::Error, routes::worker::Worker};
+
+const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Clone, Debug)]
+pub struct TrashConfig {
+    /// `None` keeps trashed contents until they are purged by hand
+    pub retention: Option<Duration>,
+}
+
+impl TrashConfig {
+    /// Read `AFTERSHOCK_TRASH_RETENTION_DAYS`, defaults to 30 days and `0` disables it.
+    pub fn from_env() -> Self {
+        let days = env::var("AFTERSHOCK_TRASH_RETENTION_DAYS")
+            .ok()
+            .and_then(|x| x.parse::<u64>().ok())
+            .unwrap_or(30);
+
+        Self {
+            retention: (days > 0).then(|| Duration::from_secs(days * 24 * 60 * 60)),
+        }
+    }
+}
+
+/// Permanently remove every content that has been in the trash longer than `retention`.
+pub fn purge_expired(retention: Duration) -> Result<Vec<aftershock_bridge::PostMeta>> {
+    let conn = &mut POOL.clone().get()?;
+    let cutoff = crate::utils::now() - retention.as_secs() as i64;
+
+    let mut ret = Worker::builder()
+        .post()
+        .deleted_before(cutoff)
+        .purge()
+        .build(conn)
+        .ok_or_else(|| Error::NotFound("Failed to build worker".into()))?
+        .load()?;
+    ret.extend(
+        Worker::builder()
+            .page()
+            .deleted_before(cutoff)
+            .purge()
+            .build(conn)
+            .ok_or_else(|| Error::NotFound("Failed to build worker".into()))?
+            .load()?,
+    );
+
+    Ok(ret)
+}
+
+/// Run [`purge_expired`] every hour for as long as the server lives.
+pub async fn schedule(config: TrashConfig) {
+    let Some(retention) = config.retention else {
+        return;
+    };
+    let mut ticker = tokio::time::interval(PURGE_INTERVAL);
+
+    loop {
+        ticker.tick().await;
+        match tokio::task::spawn_blocking(move || purge_expired(retention)).await {
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) => eprintln!("Failed to purge the trash: {e}"),
+            Err(e) => eprintln!("Trash purge did not finish: {e}"),
+        }
+    }
+}
@@ -5,6 +5,27 @@ pub fn now() -> i64 {
         .as_secs() as i64
 }
 
+pub fn hash_password(password: &str) -> crate::Result<String> {
+    use argon2::{
+        Argon2, PasswordHasher,
+        password_hash::{SaltString, rand_core::OsRng},
+    };
+
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|x| x.to_string())
+        .map_err(|e| crate::error::Error::BadRequest(e.to_string()))
+}
+
+pub fn verify_password(hash: &str, password: &str) -> bool {
+    use argon2::{Argon2, PasswordHash, PasswordVerifier};
+
+    PasswordHash::new(hash)
+        .and_then(|hash| Argon2::default().verify_password(password.as_bytes(), &hash))
+        .is_ok()
+}
+
 pub type Nid = nid::Nanoid<21, Afterbet>;
 
 pub struct Afterbet;
@@ -18,15 +39,7 @@ pub fn slugify(title: &str) -> String {
     title
         .to_lowercase()
         .chars()
-        .map(|c| {
-            if c.is_alphanumeric() {
-                c
-            } else if c.is_whitespace() {
-                '-'
-            } else {
-                '-'
-            }
-        })
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
         .collect::<String>()
         .replace("---", "-")
         .replace("--", "-")
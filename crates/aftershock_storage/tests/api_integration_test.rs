@@ -18,12 +18,12 @@ fn setup_test_env() {
         let _ = dotenvy::from_path(&env_path);
     }
     
-    if let Ok(db_url) = env::var("DATABASE_URL") {
-        if db_url.starts_with("./") {
-            let project_root = std::path::Path::new(&manifest_dir).parent().unwrap().parent().unwrap();
-            let abs_path = project_root.join(&db_url[2..]);
-            unsafe { env::set_var("DATABASE_URL", abs_path.to_str().unwrap()); }
-        }
+    if let Ok(db_url) = env::var("DATABASE_URL")
+        && let Some(relative) = db_url.strip_prefix("./")
+    {
+        let project_root = std::path::Path::new(&manifest_dir).parent().unwrap().parent().unwrap();
+        let abs_path = project_root.join(relative);
+        unsafe { env::set_var("DATABASE_URL", abs_path.to_str().unwrap()); }
     }
 }
 
@@ -71,14 +71,20 @@ async fn make_request(router: &mut Router, method: &str, uri: &str, body: Option
 }
 
 async fn create_test_item(router: &mut Router, kind: &str, published: bool) -> (String, Value) {
+    create_item_with(router, kind, json!({ "published": published })).await
+}
+
+/// Create a content from the defaults of [`create_test_item`], with `fields` replacing them
+async fn create_item_with(router: &mut Router, kind: &str, fields: Value) -> (String, Value) {
     let endpoint = if kind == "post" { "posts" } else { "pages" };
-    let payload = json!({
+    let mut payload = json!({
         "title": format!("Test {} {}", kind, uuid::Uuid::new_v4()),
         "kind": kind,
         "body": format!("Test {} body content.", kind),
         "tags": ["test"],
-        "published": published
+        "published": true
     });
+    payload.as_object_mut().unwrap().extend(fields.as_object().unwrap().clone());
 
     let (status, body) = make_request(router, "POST", &format!("{}/{}", API_V1, endpoint), Some(payload)).await;
     assert_eq!(status, 200, "Create {} failed", kind);
@@ -208,11 +214,11 @@ async fn test_invalid_creation_payloads() {
     
     // Missing 'kind' field
     let (status, _) = make_request(&mut router, "POST", &format!("{}/posts", API_V1), Some(json!({"title":"No Kind"}))).await;
-    assert!(status >= 400 && status < 500);
+    assert!((400..500).contains(&status));
 
     // Empty title
     let (status, _) = make_request(&mut router, "POST", &format!("{}/posts", API_V1), Some(json!({"title":"", "kind":"post"}))).await;
-    assert!(status >= 400 && status < 500);
+    assert!((400..500).contains(&status));
 }
 
 #[tokio::test]
@@ -242,4 +248,711 @@ async fn test_partial_update_preservation() {
     assert_eq!(body["body"], original["body"], "Body should remain unchanged");
     
     make_request(&mut router, "DELETE", &format!("{}/posts/uid/{}", API_V1, uid), None).await;
-}
\ No newline at end of file
+}
+// ===================================================================
+// Event Stream Tests
+// ===================================================================
+
+async fn open_event_stream(router: &mut Router, last_event_id: Option<i64>) -> axum::body::Body {
+    let mut request = axum::http::Request::builder()
+        .method("GET")
+        .uri(format!("{}/events", API_V1));
+    if let Some(id) = last_event_id {
+        request = request.header("Last-Event-ID", id.to_string());
+    }
+    let request = request.body(axum::body::Body::empty()).expect("Failed to build request");
+
+    let response = ServiceExt::<axum::http::Request<axum::body::Body>>::ready(router)
+        .await
+        .expect("Service not ready")
+        .call(request)
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(response.headers()["content-type"], "text/event-stream");
+    response.into_body()
+}
+
+/// Read the stream until an event about `uid` shows up, returns its id and payload
+async fn wait_for_event(body: &mut axum::body::Body, uid: &str) -> (i64, Value) {
+    let mut buffer = String::new();
+    loop {
+        let frame = tokio::time::timeout(std::time::Duration::from_secs(5), body.frame())
+            .await
+            .expect("Timed out waiting for event")
+            .expect("Event stream ended")
+            .expect("Failed to read frame");
+        if let Ok(data) = frame.into_data() {
+            buffer.push_str(&String::from_utf8_lossy(&data));
+        }
+
+        while let Some(end) = buffer.find("\n\n") {
+            let block: String = buffer.drain(..end + 2).collect();
+            let id = block.lines().find_map(|l| l.strip_prefix("id: ")).map(|x| x.parse::<i64>().unwrap());
+            let data = block.lines().find_map(|l| l.strip_prefix("data: "));
+            if let (Some(id), Some(data)) = (id, data) {
+                let data: Value = serde_json::from_str(data).unwrap();
+                if data["uid"] == uid {
+                    return (id, data);
+                }
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_event_stream_and_resume() {
+    let mut router = test_router();
+    let mut live = open_event_stream(&mut router, None).await;
+
+    let (uid, _) = create_test_item(&mut router, "post", false).await;
+    let (id, event) = wait_for_event(&mut live, &uid).await;
+    assert_eq!(event["event"], "created");
+    assert_eq!(event["kind"], "post");
+    assert!(event["timestamp"].as_i64().unwrap() > 0);
+
+    make_request(&mut router, "PUT", &format!("{}/posts/uid/{}", API_V1, uid), Some(json!({"published": true}))).await;
+    let (_, event) = wait_for_event(&mut live, &uid).await;
+    assert_eq!(event["event"], "published");
+
+    // Reconnecting from just before the creation replays everything after it
+    let mut resumed = open_event_stream(&mut router, Some(id - 1)).await;
+    let (resumed_id, event) = wait_for_event(&mut resumed, &uid).await;
+    assert_eq!(resumed_id, id);
+    assert_eq!(event["event"], "created");
+
+    make_request(&mut router, "DELETE", &format!("{}/posts/uid/{}", API_V1, uid), None).await;
+    let (_, event) = wait_for_event(&mut live, &uid).await;
+    assert_eq!(event["event"], "deleted");
+}
+
+// ===================================================================
+// Archive Export & Import Tests
+// ===================================================================
+
+use aftershock_storage::archive::{self, ImportMode};
+
+fn temp_archive_path() -> std::path::PathBuf {
+    env::temp_dir().join(format!("aftershock-archive-{}.jsonl", uuid::Uuid::new_v4()))
+}
+
+#[tokio::test]
+async fn test_archive_round_trip() {
+    let mut router = test_router();
+    let (uid, created) = create_test_item(&mut router, "post", true).await;
+
+    let path = temp_archive_path();
+    let report = archive::export(&path).expect("Export failed");
+    assert!(report.contents >= 1);
+
+    // Only keep this test's records, re-importing everything would race with the other tests
+    let exported = std::fs::read_to_string(&path).unwrap();
+    let records: Vec<Value> = exported.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+    assert_eq!(records[0]["type"], "header");
+    assert_eq!(records[0]["version"], archive::ARCHIVE_VERSION);
+    let filtered: String = records
+        .iter()
+        .filter(|r| r["type"] == "header" || r["type"] == "tag" || r["uid"] == uid.as_str())
+        .map(|r| format!("{r}\n"))
+        .collect();
+    std::fs::write(&path, filtered).unwrap();
+
+    make_request(&mut router, "DELETE", &format!("{}/posts/uid/{}", API_V1, uid), None).await;
+    make_request(&mut router, "DELETE", &format!("{}/posts/trash/{}", API_V1, uid), None).await;
+
+    // Dry run reports the missing post but leaves it missing
+    let report = archive::import(&path, ImportMode::DryRun).expect("Dry run failed");
+    assert_eq!(report.created, 1);
+    let (status, _) = make_request(&mut router, "GET", &format!("{}/posts/uid/{}", API_V1, uid), None).await;
+    assert_eq!(status, 404);
+
+    // Merge brings it back with the same uid, timestamps and tags, and says so
+    let mut live = open_event_stream(&mut router, None).await;
+    archive::import(&path, ImportMode::Merge).expect("Merge failed");
+    let (_, event) = wait_for_event(&mut live, &uid).await;
+    assert_eq!(event["event"], "created");
+    let (status, restored) = make_request(&mut router, "GET", &format!("{}/posts/uid/{}", API_V1, uid), None).await;
+    assert_eq!(status, 200);
+    assert_eq!(restored["title"], created["title"]);
+    assert_eq!(restored["created_at"], created["created_at"]);
+    assert_eq!(restored["updated_at"], created["updated_at"]);
+    assert_eq!(restored["tags"], json!(["test"]));
+
+    // Merging again updates in place instead of duplicating
+    let report = archive::import(&path, ImportMode::Merge).expect("Second merge failed");
+    assert_eq!(report.created, 0);
+    assert_eq!(report.updated, 1);
+    let (_, event) = wait_for_event(&mut live, &uid).await;
+    assert_eq!(event["event"], "updated");
+
+    make_request(&mut router, "DELETE", &format!("{}/posts/uid/{}", API_V1, uid), None).await;
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_archive_rejects_invalid_input() {
+    let mut router = test_router();
+    let uid = format!("archive-{}", uuid::Uuid::new_v4());
+    let content = json!({
+        "type": "content", "kind": "post", "uid": uid, "created_at": 1, "updated_at": 1,
+        "title": "Archived", "body": "", "published": true, "summary": null
+    });
+
+    // Missing header
+    let path = temp_archive_path();
+    std::fs::write(&path, format!("{content}\n")).unwrap();
+    assert!(archive::import(&path, ImportMode::Merge).is_err());
+
+    // Dangling tag link fails validation, the valid content before it is not written
+    let header = json!({"type": "header", "format": archive::ARCHIVE_FORMAT, "version": archive::ARCHIVE_VERSION, "exported_at": 0});
+    let link = json!({"type": "content_tag", "kind": "post", "uid": uid, "tag": "missing"});
+    std::fs::write(&path, format!("{header}\n{content}\n{link}\n")).unwrap();
+    assert!(archive::import(&path, ImportMode::Merge).is_err());
+    let (status, _) = make_request(&mut router, "GET", &format!("{}/posts/uid/{}", API_V1, uid), None).await;
+    assert_eq!(status, 404);
+
+    // Newer format versions are refused
+    let header = json!({"type": "header", "format": archive::ARCHIVE_FORMAT, "version": archive::ARCHIVE_VERSION + 1, "exported_at": 0});
+    std::fs::write(&path, format!("{header}\n")).unwrap();
+    let error = archive::import(&path, ImportMode::Merge).unwrap_err();
+    assert!(error.to_string().contains("version"));
+
+    // So are archives of another format, whatever their version
+    let header = json!({"type": "header", "format": "other-archive", "version": 1, "exported_at": 0});
+    std::fs::write(&path, format!("{header}\n")).unwrap();
+    let error = archive::import(&path, ImportMode::Merge).unwrap_err();
+    assert!(error.to_string().contains("format"));
+
+    let _ = std::fs::remove_file(&path);
+}
+
+// ===================================================================
+// Backup Tests
+// ===================================================================
+
+use aftershock_storage::backup::{self, BackupConfig};
+
+async fn run_backup(config: &BackupConfig) -> Result<backup::BackupStatus, aftershock_storage::error::Error> {
+    let config = config.clone();
+    tokio::task::spawn_blocking(move || backup::run(&config)).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_backup_snapshot_and_rotation() {
+    let mut router = test_router();
+    let dir = env::temp_dir().join(format!("aftershock-backups-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let config = BackupConfig { dir: dir.clone(), interval: None, keep_daily: 2, keep_weekly: 0 };
+
+    // Three older snapshots on separate days, only the most recent one survives rotation
+    let day = 24 * 60 * 60;
+    for age in [1, 2, 3] {
+        std::fs::write(dir.join(format!("aftershock-{}.db", 1_700_000_000 - age * day)), b"").unwrap();
+    }
+
+    let status = run_backup(&config).await.expect("Backup failed");
+    assert!(status.verified);
+    assert!(status.size > 0);
+    assert!(std::path::Path::new(&status.file).exists());
+    assert_eq!(status.removed.len(), 2);
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+
+    let (code, last) = make_request(&mut router, "GET", &format!("{}/admin/backup", API_V1), None).await;
+    assert_eq!(code, 200);
+    assert_eq!(last["file"], status.file);
+
+    // Back to back runs get their own files, the first is only removed by rotation
+    let second = run_backup(&config).await.expect("Second backup failed");
+    assert_ne!(second.file, status.file);
+    assert!(std::path::Path::new(&second.file).exists());
+    assert_eq!(second.removed, vec![status.file.clone()]);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// ===================================================================
+// Trash Tests
+// ===================================================================
+
+#[tokio::test]
+async fn test_soft_delete_restore_and_purge() {
+    let mut router = test_router();
+    let (uid, created) = create_test_item(&mut router, "post", true).await;
+
+    // Deleted content is hidden from every listing but kept in the trash
+    let (status, _) = make_request(&mut router, "DELETE", &format!("{}/posts/uid/{}", API_V1, uid), None).await;
+    assert_eq!(status, 200);
+    let (_, all) = make_request(&mut router, "GET", &format!("{}/posts/all", API_V1), None).await;
+    assert!(!all.as_array().unwrap().iter().any(|p| p["uid"] == uid));
+    let (_, tagged) = make_request(&mut router, "GET", &format!("{}/posts/tag/test", API_V1), None).await;
+    assert!(!tagged.as_array().unwrap().iter().any(|p| p["uid"] == uid));
+    let (status, trash) = make_request(&mut router, "GET", &format!("{}/posts/trash", API_V1), None).await;
+    assert_eq!(status, 200);
+    assert!(trash.as_array().unwrap().iter().any(|p| p["uid"] == uid));
+
+    // Restoring brings it back untouched
+    let (status, _) = make_request(&mut router, "POST", &format!("{}/posts/trash/{}/restore", API_V1, uid), None).await;
+    assert_eq!(status, 200);
+    let (status, restored) = make_request(&mut router, "GET", &format!("{}/posts/uid/{}", API_V1, uid), None).await;
+    assert_eq!(status, 200);
+    assert_eq!(restored["title"], created["title"]);
+    assert_eq!(restored["tags"], json!(["test"]));
+
+    // Live content can't be purged directly
+    let (status, _) = make_request(&mut router, "DELETE", &format!("{}/posts/trash/{}", API_V1, uid), None).await;
+    assert_eq!(status, 404);
+
+    // Retention keeps recent deletions and purges expired ones
+    make_request(&mut router, "DELETE", &format!("{}/posts/uid/{}", API_V1, uid), None).await;
+    let purged = aftershock_storage::trash::purge_expired(std::time::Duration::from_secs(3600)).unwrap();
+    assert!(!purged.iter().any(|p| p.uid == uid));
+    let purged = aftershock_storage::trash::purge_expired(std::time::Duration::ZERO).unwrap();
+    assert!(purged.iter().any(|p| p.uid == uid));
+
+    let (_, trash) = make_request(&mut router, "GET", &format!("{}/posts/trash", API_V1), None).await;
+    assert!(!trash.as_array().unwrap().iter().any(|p| p["uid"] == uid));
+    let (status, _) = make_request(&mut router, "POST", &format!("{}/posts/trash/{}/restore", API_V1, uid), None).await;
+    assert_eq!(status, 404);
+}
+
+// ===================================================================
+// Optimistic Concurrency Tests
+// ===================================================================
+
+async fn make_request_with_headers(
+    router: &mut Router,
+    method: &str,
+    uri: &str,
+    headers: &[(&str, &str)],
+    body: Value,
+) -> (u16, axum::http::HeaderMap, Value) {
+    let mut request = axum::http::Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("Content-Type", "application/json");
+    for (name, value) in headers {
+        request = request.header(*name, *value);
+    }
+    let request = request.body(axum::body::Body::from(body.to_string())).expect("Failed to build request");
+
+    let response = ServiceExt::<axum::http::Request<axum::body::Body>>::ready(router)
+        .await
+        .expect("Service not ready")
+        .call(request)
+        .await
+        .expect("Request failed");
+
+    let status = response.status().as_u16();
+    let headers = response.headers().clone();
+    let body_bytes = response.into_body().collect().await.expect("Failed to collect body").to_bytes();
+    (status, headers, serde_json::from_slice(&body_bytes).unwrap_or(Value::Null))
+}
+
+#[tokio::test]
+async fn test_revision_and_if_match() {
+    let mut router = test_router();
+    let (uid, created) = create_test_item(&mut router, "post", true).await;
+    assert_eq!(created["revision"], 1);
+    let uri = format!("{}/posts/uid/{}", API_V1, uid);
+
+    let (status, headers, _) = make_request_with_headers(&mut router, "GET", &uri, &[], Value::Null).await;
+    assert_eq!(status, 200);
+    assert_eq!(headers["etag"], "\"1\"");
+
+    // Matching revision wins and bumps the revision
+    let (status, headers, body) = make_request_with_headers(&mut router, "PUT", &uri, &[("If-Match", "\"1\"")], json!({"title": "First"})).await;
+    assert_eq!(status, 200);
+    assert_eq!(body["revision"], 2);
+    assert_eq!(headers["etag"], "\"2\"");
+
+    // A stale revision is refused with the current server version
+    let (status, headers, body) = make_request_with_headers(&mut router, "PUT", &uri, &[("If-Match", "\"1\"")], json!({"title": "Second"})).await;
+    assert_eq!(status, 412);
+    assert_eq!(headers["etag"], "\"2\"");
+    assert_eq!(body["revision"], 2);
+    assert_eq!(body["title"], "First");
+
+    // Without a precondition the update always goes through
+    let (status, _, body) = make_request_with_headers(&mut router, "PUT", &uri, &[], json!({"title": "Third"})).await;
+    assert_eq!(status, 200);
+    assert_eq!(body["revision"], 3);
+
+    let (status, _, _) = make_request_with_headers(&mut router, "PUT", &uri, &[("If-Match", "nonsense")], json!({"title": "x"})).await;
+    assert_eq!(status, 400);
+    let (status, _, _) = make_request_with_headers(&mut router, "PUT", &format!("{}/posts/uid/nonexistent-123", API_V1), &[("If-Match", "\"1\"")], json!({"title": "x"})).await;
+    assert_eq!(status, 404);
+
+    make_request(&mut router, "DELETE", &uri, None).await;
+}
+
+#[tokio::test]
+async fn test_unlisted_and_protected_visibility() {
+    let mut router = test_router();
+    let tag = format!("vis-{}", uuid::Uuid::new_v4());
+    let fields = |visibility: &str| json!({ "body": "Secret body", "tags": [tag], "visibility": visibility });
+
+    let (unlisted_uid, unlisted) = create_item_with(&mut router, "post", fields("unlisted")).await;
+    assert_eq!(unlisted["visibility"], "unlisted");
+
+    let mut protected = fields("protected");
+    protected["password"] = json!("hunter2");
+    let (protected_uid, _) = create_item_with(&mut router, "post", protected).await;
+
+    let no_password = json!({"title": "Visibility protected", "kind": "post", "body": "", "tags": [], "published": true, "visibility": "protected"});
+    let (status, _) = make_request(&mut router, "POST", &format!("{}/posts", API_V1), Some(no_password)).await;
+    assert_eq!(status, 400);
+
+    // Unlisted stays out of every listing but is reachable by uid
+    for uri in [format!("{}/posts", API_V1), format!("{}/posts/meta", API_V1), format!("{}/posts/tag/{}", API_V1, tag)] {
+        let (status, list) = make_request(&mut router, "GET", &uri, None).await;
+        assert_eq!(status, 200);
+        let uids: Vec<&str> = list.as_array().unwrap().iter().map(|x| x["uid"].as_str().unwrap()).collect();
+        assert!(!uids.contains(&unlisted_uid.as_str()), "{} lists an unlisted post", uri);
+        assert!(uids.contains(&protected_uid.as_str()), "{} misses a protected post", uri);
+    }
+    let (status, read) = make_request(&mut router, "GET", &format!("{}/posts/uid/{}", API_V1, unlisted_uid), None).await;
+    assert_eq!(status, 200);
+    assert_eq!(read["body"], "Secret body");
+
+    // Protected bodies are withheld until the password is given
+    let (status, read) = make_request(&mut router, "GET", &format!("{}/posts/uid/{}", API_V1, protected_uid), None).await;
+    assert_eq!(status, 200);
+    assert_eq!(read["visibility"], "protected");
+    assert_eq!(read["body"], "");
+    let unlock_uri = format!("{}/posts/uid/{}/unlock", API_V1, protected_uid);
+    let (status, _) = make_request(&mut router, "POST", &unlock_uri, Some(json!({"password": "wrong"}))).await;
+    assert_eq!(status, 403);
+    let (status, read) = make_request(&mut router, "POST", &unlock_uri, Some(json!({"password": "hunter2"}))).await;
+    assert_eq!(status, 200);
+    assert_eq!(read["body"], "Secret body");
+    let (status, _) = make_request(&mut router, "POST", &format!("{}/posts/uid/{}/unlock", API_V1, unlisted_uid), Some(json!({"password": "hunter2"}))).await;
+    assert_eq!(status, 404);
+
+    // Private is reported for drafts, choosing it unpublishes
+    let (status, updated) = make_request(&mut router, "PUT", &format!("{}/posts/uid/{}", API_V1, unlisted_uid), Some(json!({"visibility": "private"}))).await;
+    assert_eq!(status, 200);
+    assert_eq!(updated["published"], false);
+    assert_eq!(updated["visibility"], "private");
+
+    for uid in [unlisted_uid, protected_uid] {
+        make_request(&mut router, "DELETE", &format!("{}/posts/uid/{}", API_V1, uid), None).await;
+    }
+}
+
+#[tokio::test]
+async fn test_protecting_on_update_needs_a_password() {
+    let mut router = test_router();
+    let (uid, created) = create_test_item(&mut router, "post", true).await;
+    let uri = format!("{}/posts/uid/{}", API_V1, uid);
+    let unlock_uri = format!("{}/unlock", uri);
+
+    // Without a stored or a new password nothing changes
+    let (status, _) = make_request(&mut router, "PUT", &uri, Some(json!({"visibility": "protected"}))).await;
+    assert_eq!(status, 400);
+    let (_, read) = make_request(&mut router, "GET", &uri, None).await;
+    assert_eq!(read["visibility"], "public");
+    assert_eq!(read["revision"], created["revision"]);
+
+    let (status, _) = make_request(&mut router, "PUT", &uri, Some(json!({"visibility": "protected", "password": "hunter2"}))).await;
+    assert_eq!(status, 200);
+    // The stored password carries over to later updates
+    let (status, _) = make_request(&mut router, "PUT", &uri, Some(json!({"visibility": "protected", "title": "Still protected"}))).await;
+    assert_eq!(status, 200);
+    let (status, _) = make_request(&mut router, "POST", &unlock_uri, Some(json!({"password": "hunter2"}))).await;
+    assert_eq!(status, 200);
+
+    // Leaving protected forgets the password
+    let (status, _) = make_request(&mut router, "PUT", &uri, Some(json!({"visibility": "public"}))).await;
+    assert_eq!(status, 200);
+    let (status, _) = make_request(&mut router, "PUT", &uri, Some(json!({"visibility": "protected"}))).await;
+    assert_eq!(status, 400);
+
+    make_request(&mut router, "DELETE", &uri, None).await;
+}
+
+#[tokio::test]
+async fn test_draft_preview_token() {
+    let mut router = test_router();
+    let (uid, _) = create_test_item(&mut router, "post", false).await;
+
+    let (status, preview) = make_request(&mut router, "POST", &format!("{}/posts/uid/{}/preview", API_V1, uid), None).await;
+    assert_eq!(status, 200);
+    let token = preview["token"].as_str().unwrap().to_string();
+    assert!(preview["expires_at"].as_i64().unwrap() > 0);
+
+    // The draft stays hidden from the public route but shows through the token
+    let (status, _) = make_request(&mut router, "GET", &format!("{}/posts/uid/{}", API_V1, uid), None).await;
+    assert_eq!(status, 404);
+    let (status, read) = make_request(&mut router, "GET", &format!("{}/preview/{}", API_V1, token), None).await;
+    assert_eq!(status, 200);
+    assert_eq!(read["uid"], uid);
+    assert_eq!(read["published"], false);
+
+    // Tampered and expired tokens are refused
+    let (payload, signature) = token.split_once('.').unwrap();
+    let tampered = format!("{}x.{}", payload, signature);
+    let (status, _) = make_request(&mut router, "GET", &format!("{}/preview/{}", API_V1, tampered), None).await;
+    assert_eq!(status, 403);
+    let (status, _) = make_request(&mut router, "GET", &format!("{}/preview/garbage", API_V1), None).await;
+    assert_eq!(status, 403);
+    let (status, expired) = make_request(&mut router, "POST", &format!("{}/posts/uid/{}/preview?ttl=0", API_V1, uid), None).await;
+    assert_eq!(status, 200);
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+    let (status, _) = make_request(&mut router, "GET", &format!("{}/preview/{}", API_V1, expired["token"].as_str().unwrap()), None).await;
+    assert_eq!(status, 403);
+
+    let (status, _) = make_request(&mut router, "POST", &format!("{}/posts/uid/{}/preview?ttl={}", API_V1, uid, u64::MAX), None).await;
+    assert_eq!(status, 400);
+
+    let (status, _) = make_request(&mut router, "POST", &format!("{}/posts/uid/nonexistent-123/preview", API_V1), None).await;
+    assert_eq!(status, 404);
+
+    make_request(&mut router, "DELETE", &format!("{}/posts/uid/{}", API_V1, uid), None).await;
+}
+
+#[tokio::test]
+async fn test_page_nav_front_matter() {
+    let mut router = test_router();
+    let payload = json!({
+        "title": format!("Nav {}", uuid::Uuid::new_v4()),
+        "kind": "page",
+        "body": "Page body",
+        "tags": [],
+        "published": true,
+        "nav": {"order": 3, "title": "Short"}
+    });
+    let (status, created) = make_request(&mut router, "POST", &format!("{}/pages", API_V1), Some(payload)).await;
+    assert_eq!(status, 200);
+    assert_eq!(created["nav"], json!({"order": 3, "title": "Short"}));
+    let uid = created["uid"].as_str().unwrap().to_string();
+    let uri = format!("{}/pages/uid/{}", API_V1, uid);
+
+    let (_, meta) = make_request(&mut router, "GET", &format!("{}/pages/meta", API_V1), None).await;
+    let listed = meta.as_array().unwrap().iter().find(|x| x["uid"] == uid.as_str()).expect("page is listed");
+    assert_eq!(listed["nav"]["order"], 3);
+
+    // Leaving nav out keeps it, null removes it
+    let (_, updated) = make_request(&mut router, "PUT", &uri, Some(json!({"title": "Renamed"}))).await;
+    assert_eq!(updated["nav"]["title"], "Short");
+    let (_, updated) = make_request(&mut router, "PUT", &uri, Some(json!({"nav": {"order": 1}}))).await;
+    assert_eq!(updated["nav"], json!({"order": 1, "title": null}));
+    let (_, updated) = make_request(&mut router, "PUT", &uri, Some(json!({"nav": null}))).await;
+    assert_eq!(updated["nav"], Value::Null);
+
+    make_request(&mut router, "DELETE", &uri, None).await;
+}
+
+#[tokio::test]
+async fn test_paginated_meta() {
+    let mut router = test_router();
+    let tag = format!("paged-{}", uuid::Uuid::new_v4());
+    let mut uids = vec![];
+    for _ in 0..3 {
+        let (uid, _) = create_item_with(&mut router, "post", json!({ "tags": [tag] })).await;
+        uids.push(uid);
+    }
+
+    let tag_page = |page: i64| format!("{}/posts/tag/{}/meta/page/{}?per_page=2", API_V1, tag, page);
+    let (status, first) = make_request(&mut router, "GET", &tag_page(1), None).await;
+    assert_eq!(status, 200);
+    assert_eq!(first["total"], 3);
+    assert_eq!(first["total_pages"], 2);
+    assert_eq!(first["items"].as_array().unwrap().len(), 2);
+    assert!(first["items"][0].get("body").is_none() || first["items"][0]["body"].is_null());
+    let (_, second) = make_request(&mut router, "GET", &tag_page(2), None).await;
+    assert_eq!(second["page"], 2);
+    assert_eq!(second["items"].as_array().unwrap().len(), 1);
+
+    // Pages never overlap
+    let seen: std::collections::HashSet<_> = first["items"].as_array().unwrap().iter()
+        .chain(second["items"].as_array().unwrap())
+        .map(|x| x["uid"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(seen.len(), 3);
+
+    let (status, _) = make_request(&mut router, "GET", &tag_page(3), None).await;
+    assert_eq!(status, 404);
+    let (status, _) = make_request(&mut router, "GET", &tag_page(0), None).await;
+    assert_eq!(status, 400);
+    let (status, _) = make_request(&mut router, "GET", &format!("{}/posts/meta/page/1?per_page=0", API_V1), None).await;
+    assert_eq!(status, 400);
+    let (status, all) = make_request(&mut router, "GET", &format!("{}/posts/meta/page/1", API_V1), None).await;
+    assert_eq!(status, 200);
+    assert_eq!(all["per_page"], 10);
+
+    for uid in uids {
+        make_request(&mut router, "DELETE", &format!("{}/posts/uid/{}", API_V1, uid), None).await;
+    }
+}
+
+#[tokio::test]
+async fn test_search_posts() {
+    let mut router = test_router();
+    let word = format!("needle{}", uuid::Uuid::new_v4().simple());
+    let body = format!("<p>Some text before the <span class=\"hl\">{} Haystack</span> and after.</p>", word);
+    let (uid, _) = create_item_with(&mut router, "post", json!({ "title": format!("Search {}", word), "body": body })).await;
+    let search = |q: &str| format!("{}/posts/search?q={}", API_V1, q);
+
+    let (status, hits) = make_request(&mut router, "GET", &search(&format!("{}+HAYSTACK", word)), None).await;
+    assert_eq!(status, 200);
+    let hits = hits.as_array().unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0]["post"]["uid"], uid.as_str());
+    assert!(hits[0]["post"].get("body").is_none() || hits[0]["post"]["body"].is_null());
+    let marked: Vec<_> = hits[0]["snippet"].as_array().unwrap().iter()
+        .filter(|x| x["highlight"] == true)
+        .map(|x| x["text"].as_str().unwrap().to_lowercase())
+        .collect();
+    assert_eq!(marked, vec![word.clone(), "haystack".to_string()]);
+    let text: String = hits[0]["snippet"].as_array().unwrap().iter().map(|x| x["text"].as_str().unwrap()).collect();
+    assert!(!text.contains('<'));
+
+    // Every term has to match, and markup is not searched
+    let (_, hits) = make_request(&mut router, "GET", &search(&format!("{}+missing", word)), None).await;
+    assert!(hits.as_array().unwrap().is_empty());
+    let (_, hits) = make_request(&mut router, "GET", &search(&format!("{}+span", word)), None).await;
+    assert!(hits.as_array().unwrap().is_empty());
+    let (status, hits) = make_request(&mut router, "GET", &search(""), None).await;
+    assert_eq!(status, 200);
+    assert!(hits.as_array().unwrap().is_empty());
+
+    // Protected bodies are never searched
+    let uri = format!("{}/posts/uid/{}", API_V1, uid);
+    make_request(&mut router, "PUT", &uri, Some(json!({"title": "Locked", "visibility": "protected", "password": "pw"}))).await;
+    let (_, hits) = make_request(&mut router, "GET", &search(&word), None).await;
+    assert!(hits.as_array().unwrap().is_empty());
+
+    make_request(&mut router, "DELETE", &uri, None).await;
+}
+
+#[tokio::test]
+async fn test_post_tag_counts() {
+    let mut router = test_router();
+    let tag = format!("counted-{}", uuid::Uuid::new_v4());
+    let mut uris = vec![];
+    for (published, visibility) in [(true, "public"), (true, "public"), (true, "unlisted"), (false, "public")] {
+        let fields = json!({ "tags": [tag], "published": published, "visibility": visibility });
+        let (uid, _) = create_item_with(&mut router, "post", fields).await;
+        uris.push(format!("{}/posts/uid/{}", API_V1, uid));
+    }
+
+    let count = |tags: &Value| tags.as_array().unwrap().iter().find(|x| x["tag"] == tag.as_str()).map(|x| x["count"].clone());
+    let (status, tags) = make_request(&mut router, "GET", &format!("{}/posts/tags", API_V1), None).await;
+    assert_eq!(status, 200);
+    assert_eq!(count(&tags), Some(json!(2)));
+    let names: Vec<_> = tags.as_array().unwrap().iter().map(|x| x["tag"].as_str().unwrap().to_string()).collect();
+    let mut sorted = names.clone();
+    sorted.sort();
+    assert_eq!(names, sorted);
+
+    // Trashed posts stop counting
+    make_request(&mut router, "DELETE", &uris[0], None).await;
+    let (_, tags) = make_request(&mut router, "GET", &format!("{}/posts/tags", API_V1), None).await;
+    assert_eq!(count(&tags), Some(json!(1)));
+
+    for uri in &uris[1..] {
+        make_request(&mut router, "DELETE", uri, None).await;
+    }
+    let (_, tags) = make_request(&mut router, "GET", &format!("{}/posts/tags", API_V1), None).await;
+    assert_eq!(count(&tags), None);
+}
+
+#[tokio::test]
+async fn test_post_archive_by_period() {
+    use chrono::Datelike;
+
+    let mut router = test_router();
+    let (uid, created) = create_test_item(&mut router, "post", true).await;
+    let tz = "Pacific/Kiritimati";
+    let now = chrono::DateTime::from_timestamp(created["created_at"].as_i64().unwrap(), 0)
+        .unwrap()
+        .with_timezone(&tz.parse::<chrono_tz::Tz>().unwrap());
+    let (year, month) = (now.year(), now.month());
+    let has_post = |posts: &Value| posts.as_array().unwrap().iter().any(|x| x["uid"] == uid.as_str());
+
+    let (status, posts) = make_request(&mut router, "GET", &format!("{}/posts/archive/{}/{}?tz={}", API_V1, year, month, tz), None).await;
+    assert_eq!(status, 200);
+    assert!(has_post(&posts));
+    let (_, posts) = make_request(&mut router, "GET", &format!("{}/posts/archive/{}?tz={}", API_V1, year, tz), None).await;
+    assert!(has_post(&posts));
+    let (_, posts) = make_request(&mut router, "GET", &format!("{}/posts/archive/{}?tz={}", API_V1, year - 1, tz), None).await;
+    assert!(!has_post(&posts));
+
+    let (status, months) = make_request(&mut router, "GET", &format!("{}/posts/archive?tz={}", API_V1, tz), None).await;
+    assert_eq!(status, 200);
+    let months = months.as_array().unwrap();
+    let current = months.iter().find(|x| x["year"] == year && x["month"] == month).expect("month is counted");
+    assert!(current["count"].as_i64().unwrap() >= 1);
+    let keys: Vec<_> = months.iter().map(|x| (x["year"].as_i64().unwrap(), x["month"].as_i64().unwrap())).collect();
+    assert!(keys.windows(2).all(|x| x[0] > x[1]));
+
+    let (status, _) = make_request(&mut router, "GET", &format!("{}/posts/archive/{}/13", API_V1, year), None).await;
+    assert_eq!(status, 400);
+    let (status, _) = make_request(&mut router, "GET", &format!("{}/posts/archive/{}", API_V1, i32::MAX), None).await;
+    assert_eq!(status, 400);
+    let (status, _) = make_request(&mut router, "GET", &format!("{}/posts/archive/{}/{}", API_V1, year, u32::MAX), None).await;
+    assert_eq!(status, 400);
+    let (status, _) = make_request(&mut router, "GET", &format!("{}/posts/archive?tz=Mars/Olympus", API_V1), None).await;
+    assert_eq!(status, 400);
+
+    make_request(&mut router, "DELETE", &format!("{}/posts/uid/{}", API_V1, uid), None).await;
+}
+
+#[tokio::test]
+async fn test_post_neighbors_and_related() {
+    let mut router = test_router();
+    let (x, y) = (format!("x-{}", uuid::Uuid::new_v4()), format!("y-{}", uuid::Uuid::new_v4()));
+    let mut created = vec![];
+    for (tags, visibility) in [(vec![&x, &y], "public"), (vec![&x], "public"), (vec![&x, &y], "unlisted"), (vec![&x, &y], "public")] {
+        let (_, post) = create_item_with(&mut router, "post", json!({ "tags": tags, "visibility": visibility })).await;
+        created.push(post);
+    }
+    let uid = |i: usize| created[i]["uid"].as_str().unwrap().to_string();
+    let neighbors = |i: usize| format!("{}/posts/uid/{}/neighbors", API_V1, uid(i));
+
+    // Most shared tags first, the unlisted post never shows up
+    let (status, first) = make_request(&mut router, "GET", &neighbors(0), None).await;
+    assert_eq!(status, 200);
+    let related: Vec<_> = first["related"].as_array().unwrap().iter().map(|x| x["uid"].as_str().unwrap().to_string()).collect();
+    assert_eq!(related, vec![uid(3), uid(1)]);
+    assert!(first["next"]["created_at"].as_i64().unwrap() >= created[0]["created_at"].as_i64().unwrap());
+    assert_ne!(first["next"]["uid"], uid(2).as_str());
+
+    let (_, last) = make_request(&mut router, "GET", &neighbors(3), None).await;
+    assert!(last["prev"]["created_at"].as_i64().unwrap() <= created[3]["created_at"].as_i64().unwrap());
+    assert_ne!(last["prev"]["uid"], uid(2).as_str());
+    assert!(last["related"].as_array().unwrap().iter().all(|x| x["uid"] != uid(2).as_str()));
+
+    // Unlisted posts still have neighbors, unknown ones do not
+    let (status, _) = make_request(&mut router, "GET", &neighbors(2), None).await;
+    assert_eq!(status, 200);
+    let (status, _) = make_request(&mut router, "GET", &format!("{}/posts/uid/nonexistent-neighbor/neighbors", API_V1), None).await;
+    assert_eq!(status, 404);
+
+    for i in 0..created.len() {
+        make_request(&mut router, "DELETE", &format!("{}/posts/uid/{}", API_V1, uid(i)), None).await;
+    }
+}
+
+#[tokio::test]
+async fn test_post_toc() {
+    let mut router = test_router();
+    let toc = json!([
+        {"level": 2, "id": "你好世界", "title": "你好，世界！"},
+        {"level": 3, "id": "details", "title": "Details"}
+    ]);
+    let body = "<h2 id=\"你好世界\">你好，世界！</h2><h3 id=\"details\">Details</h3>";
+    let (uid, created) = create_item_with(&mut router, "post", json!({ "body": body, "tags": [], "toc": toc })).await;
+    assert_eq!(created["toc"], toc);
+    let uri = format!("{}/posts/uid/{}", API_V1, uid);
+    let (_, read) = make_request(&mut router, "GET", &uri, None).await;
+    assert_eq!(read["toc"], toc);
+
+    // Kept when only the title changes, replaced along with the body
+    let (_, updated) = make_request(&mut router, "PUT", &uri, Some(json!({"title": "Renamed"}))).await;
+    assert_eq!(updated["toc"], toc);
+    let (_, updated) = make_request(&mut router, "PUT", &uri, Some(json!({"body": "<p>Flat</p>", "toc": []}))).await;
+    assert_eq!(updated["toc"], json!([]));
+
+    make_request(&mut router, "DELETE", &uri, None).await;
+}
@@ -0,0 +1,8 @@
+-- Your SQL goes here
+CREATE TABLE events (
+  id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+  event TEXT NOT NULL,
+  uid TEXT NOT NULL,
+  kind TEXT NOT NULL,
+  created_at BIGINT NOT NULL
+);
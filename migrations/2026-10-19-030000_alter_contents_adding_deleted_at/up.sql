@@ -0,0 +1,2 @@
+-- Your SQL goes here
+ALTER TABLE contents ADD COLUMN deleted_at BIGINT;
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+ALTER TABLE contents ADD COLUMN visibility TEXT NOT NULL DEFAULT 'public';
+ALTER TABLE contents ADD COLUMN password_hash TEXT;
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE contents DROP COLUMN nav_title;
+ALTER TABLE contents DROP COLUMN nav_order;
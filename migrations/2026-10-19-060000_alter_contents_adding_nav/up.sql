@@ -0,0 +1,6 @@
+-- Your SQL goes here
+ALTER TABLE contents ADD COLUMN nav_order INTEGER;
+ALTER TABLE contents ADD COLUMN nav_title TEXT;
+
+-- The about page used to be linked from the header unconditionally
+UPDATE contents SET nav_order = 1 WHERE kind = 'page' AND uid = 'about';
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+-- Heading outline of the body, a JSON array of {level, id, title}
+ALTER TABLE contents ADD COLUMN toc TEXT;